@@ -53,10 +53,7 @@ impl Handler for CreateUser {
 
     async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
         handle! {
-            // Validate the request - errors auto-convert to HTTP 422
-            ctx.req.validate()?;
-
-            // Business logic
+            // The request was validated before the handler ran (see `register_validated`)
             let user = UserResponse {
                 id: 1,
                 name: ctx.req.name,
@@ -109,7 +106,8 @@ async fn main() {
     uncovr::server::Server::new()
         .with_config(app)
         .with_logging(Logging::development())
-        .register(CreateUser)
+        // Invalid requests are rejected with HTTP 422 before reaching the handler
+        .register_validated(CreateUser)
         .register(SimpleEndpoint)
         .serve()
         .await
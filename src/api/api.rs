@@ -11,7 +11,7 @@ use crate::context::Context;
 ///
 /// # Type Safety
 ///
-/// - **Request**: Must be deserializable and have a default (for GET requests without body)
+/// - **Request**: Must be deserializable; see [`Handler::Request`] for routes without a body
 /// - **Response**: Must be convertible to HTTP response
 /// - **Async**: Returns a `Send` future for async runtime compatibility
 ///
@@ -23,6 +23,8 @@ use crate::context::Context;
 /// - Query parameters via `ctx.query.get()` or `ctx.query.parse()`
 /// - Application state via `ctx.state::<T>()`
 /// - HTTP headers via `ctx.headers`
+/// - Request method, URI, matched route and remote address via `ctx.method`,
+///   `ctx.uri`, `ctx.matched_path` and `ctx.remote_addr`
 ///
 /// # Example
 ///
@@ -56,7 +58,17 @@ use crate::context::Context;
 #[async_trait::async_trait]
 pub trait Handler {
     /// Request body type for this handler.
-    type Request: DeserializeOwned + Default + Send + 'static;
+    ///
+    /// When a route reads no body, or an optional body is absent (see
+    /// [`BodyPolicy`](crate::server::BodyPolicy)), the request value is deserialized
+    /// from JSON `null` or, failing that, from an empty object. This covers `()`,
+    /// `Option<T>` and structs marked `#[serde(default)]`. Types that cannot be
+    /// built that way should only be used with [`BodyPolicy::Required`](crate::server::BodyPolicy::Required);
+    /// otherwise requests without a body are rejected with a 400 `missing_body` error, and
+    /// registering a route that reads no body panics. Types that implement `Default` can be
+    /// registered with [`ServerBuilder::register_default()`](crate::server::ServerBuilder::register_default)
+    /// instead, which uses `Default::default()` when no body is sent.
+    type Request: DeserializeOwned + Send + 'static;
 
     /// Response type for this handler.
     type Response: Send + 'static;
@@ -107,6 +119,10 @@ mod tests {
                 message: "hello".into(),
             },
             headers: Arc::new(HeaderMap::new()),
+            method: http::Method::POST,
+            uri: http::Uri::default(),
+            matched_path: None,
+            remote_addr: None,
             path: crate::server::Path::empty(),
             query: crate::server::Query::empty(),
             extensions: Extensions::new(),
@@ -0,0 +1,499 @@
+//! Filtering and sorting of list endpoints from the query string.
+//!
+//! A route declares the fields clients may filter and sort on with a [`FilterSpec`];
+//! the query string is parsed into a [`ListQuery`] before the handler runs:
+//!
+//! - `?status=active` filters with `eq`, `?created_at[gt]=2024-01-01` with any other operator
+//! - `?status[in]=active,pending` takes a comma-separated list
+//! - `?sort=-created_at,name` sorts descending on `created_at`, then ascending on `name`
+
+use std::fmt;
+
+use aide::openapi::{
+    Operation, Parameter, ParameterData, ParameterSchemaOrContent, QueryStyle, ReferenceOr,
+    SchemaObject,
+};
+use schemars::schema::InstanceType;
+
+use crate::api::response::Error;
+
+/// Comparison operator of a filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FilterOp {
+    /// `field=value` or `field[eq]=value`
+    Eq,
+    /// `field[ne]=value`
+    Ne,
+    /// `field[lt]=value`
+    Lt,
+    /// `field[lte]=value`
+    Lte,
+    /// `field[gt]=value`
+    Gt,
+    /// `field[gte]=value`
+    Gte,
+    /// `field[in]=a,b,c`
+    In,
+    /// `field[contains]=value`
+    Contains,
+}
+
+impl FilterOp {
+    /// All operators, in documentation order
+    pub const ALL: [FilterOp; 8] = [
+        Self::Eq,
+        Self::Ne,
+        Self::Lt,
+        Self::Lte,
+        Self::Gt,
+        Self::Gte,
+        Self::In,
+        Self::Contains,
+    ];
+
+    /// Name of the operator in the query string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::In => "in",
+            Self::Contains => "contains",
+        }
+    }
+
+    /// Equivalent SQL operator; `Contains` maps to `LIKE` with the value wrapped in `%`
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "<>",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::In => "IN",
+            Self::Contains => "LIKE",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.as_str() == name)
+    }
+}
+
+impl fmt::Display for FilterOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Sort direction of a [`SortKey`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortDirection {
+    /// `sort=field`
+    Asc,
+    /// `sort=-field`
+    Desc,
+}
+
+impl SortDirection {
+    /// `ASC` or `DESC`
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FilterField {
+    name: &'static str,
+    ops: Vec<FilterOp>,
+}
+
+/// Fields a list route can be filtered and sorted on, declared with
+/// [`Route::filter()`](crate::server::Route::filter).
+///
+/// Unknown or disallowed operators on declared fields and sorts on undeclared
+/// fields are rejected with a 400 `invalid_filter` or `invalid_sort` error. Query
+/// parameters whose name, before any `[...]`, is not a declared field are left to
+/// the handler, so nested objects such as `?address[city]=x` can be read with
+/// [`Query::get_object()`](crate::server::params::Query::get_object).
+///
+/// # Example
+///
+/// ```rust
+/// use uncovr::api::{FilterOp, FilterSpec};
+/// use uncovr::server::Route;
+///
+/// let route = Route::get("/orders").filter(
+///     FilterSpec::new()
+///         .field("status", [FilterOp::Eq, FilterOp::In])
+///         .field("total", [FilterOp::Gte, FilterOp::Lte])
+///         .sortable(["created_at", "total"])
+///         .default_sort("-created_at"),
+/// );
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FilterSpec {
+    fields: Vec<FilterField>,
+    sortable: Vec<&'static str>,
+    default_sort: Option<&'static str>,
+}
+
+impl FilterSpec {
+    /// Creates a spec with no filterable or sortable fields.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows filtering on `name` with the given operators.
+    pub fn field(mut self, name: &'static str, ops: impl IntoIterator<Item = FilterOp>) -> Self {
+        let ops = ops.into_iter().collect();
+        match self.fields.iter_mut().find(|field| field.name == name) {
+            Some(field) => field.ops = ops,
+            None => self.fields.push(FilterField { name, ops }),
+        }
+        self
+    }
+
+    /// Allows sorting on the given fields.
+    pub fn sortable(mut self, fields: impl IntoIterator<Item = &'static str>) -> Self {
+        self.sortable.extend(fields);
+        self
+    }
+
+    /// Sets the sort used when the request has no `sort` parameter, e.g. `"-created_at,name"`.
+    ///
+    /// The fields must be declared with [`sortable()`](Self::sortable), before or after this
+    /// call; the sort is checked when the spec is attached with
+    /// [`Route::filter()`](crate::server::Route::filter).
+    pub fn default_sort(mut self, sort: &'static str) -> Self {
+        self.default_sort = Some(sort);
+        self
+    }
+
+    /// Panics if the default sort names a field that is not sortable.
+    pub(crate) fn check_default_sort(&self) {
+        if let Some(sort) = self.default_sort {
+            if let Err(err) = self.parse_sort(sort) {
+                panic!("Invalid default sort `{}`: {}", sort, err);
+            }
+        }
+    }
+
+    /// Parses the filters and sort of a query string.
+    pub fn parse(&self, query: Option<&str>) -> Result<ListQuery, Error> {
+        let pairs = form_urlencoded::parse(query.unwrap_or_default().as_bytes()).into_owned();
+
+        let mut filters = Vec::new();
+        let mut sort = None;
+        for (key, value) in pairs {
+            if key == "sort" {
+                sort = Some(self.parse_sort(&value)?);
+                continue;
+            }
+
+            let (name, op) = match key.strip_suffix(']').and_then(|key| key.split_once('[')) {
+                Some((name, op)) => (name, Some(op)),
+                None => (key.as_str(), None),
+            };
+
+            // Other parameters, including nested objects such as `address[city]`, may
+            // belong to the handler
+            let Some(field) = self.fields.iter().find(|field| field.name == name) else {
+                continue;
+            };
+            let op = match op {
+                Some(op) => FilterOp::parse(op).ok_or_else(|| {
+                    Error::bad_request_with_details(
+                        "invalid_filter",
+                        format!("Unknown filter operator `{}` in `{}`", op, key),
+                        serde_json::json!({ "operators": FilterOp::ALL.map(|op| op.as_str()) }),
+                    )
+                })?,
+                None => FilterOp::Eq,
+            };
+            if !field.ops.contains(&op) {
+                let operators: Vec<_> = field.ops.iter().map(FilterOp::as_str).collect();
+                return Err(Error::bad_request_with_details(
+                    "invalid_filter",
+                    format!("Operator `{}` is not supported on `{}`", op, name),
+                    serde_json::json!({ "operators": operators }),
+                ));
+            }
+
+            let values = match op {
+                FilterOp::In => value
+                    .split(',')
+                    .filter(|value| !value.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                _ => vec![value],
+            };
+            filters.push(Filter {
+                field: field.name,
+                op,
+                values,
+            });
+        }
+
+        let sort = match (sort, self.default_sort) {
+            (Some(sort), _) => sort,
+            (None, Some(default)) => self.parse_sort(default)?,
+            (None, None) => Vec::new(),
+        };
+        Ok(ListQuery { filters, sort })
+    }
+
+    fn parse_sort(&self, sort: &str) -> Result<Vec<SortKey>, Error> {
+        sort.split(',')
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let (name, direction) = match key.strip_prefix('-') {
+                    Some(name) => (name, SortDirection::Desc),
+                    None => (key.strip_prefix('+').unwrap_or(key), SortDirection::Asc),
+                };
+                let field = self
+                    .sortable
+                    .iter()
+                    .find(|field| **field == name)
+                    .ok_or_else(|| {
+                        Error::bad_request_with_details(
+                            "invalid_sort",
+                            format!("Sorting on `{}` is not supported", name),
+                            serde_json::json!({ "fields": self.sortable }),
+                        )
+                    })?;
+                Ok(SortKey { field, direction })
+            })
+            .collect()
+    }
+
+    /// Documents the filter and `sort` query parameters on an operation.
+    pub(crate) fn document(&self, operation: &mut Operation) {
+        for field in &self.fields {
+            for op in &field.ops {
+                let (name, description) = match op {
+                    FilterOp::Eq => (
+                        field.name.to_string(),
+                        format!("Filter on `{}`", field.name),
+                    ),
+                    FilterOp::In => (
+                        format!("{}[in]", field.name),
+                        format!(
+                            "Filter on `{}` matching any of a comma-separated list",
+                            field.name
+                        ),
+                    ),
+                    op => (
+                        format!("{}[{}]", field.name, op),
+                        format!("Filter on `{}` with the `{}` operator", field.name, op),
+                    ),
+                };
+                push_parameter(operation, name, description);
+            }
+        }
+
+        if !self.sortable.is_empty() {
+            let mut description = format!(
+                "Comma-separated fields to sort on, prefixed with `-` for descending order. \
+                 Sortable fields: {}",
+                self.sortable.join(", ")
+            );
+            if let Some(default) = self.default_sort {
+                description.push_str(&format!(". Default: `{}`", default));
+            }
+            push_parameter(operation, "sort".to_string(), description);
+        }
+    }
+}
+
+fn push_parameter(operation: &mut Operation, name: String, description: String) {
+    let exists = operation.parameters.iter().any(|param| match param {
+        ReferenceOr::Item(param) => param.parameter_data_ref().name == name,
+        ReferenceOr::Reference { .. } => false,
+    });
+    if exists {
+        return;
+    }
+
+    let schema = schemars::schema::SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        ..Default::default()
+    };
+    operation
+        .parameters
+        .push(ReferenceOr::Item(Parameter::Query {
+            parameter_data: ParameterData {
+                name,
+                description: Some(description),
+                required: false,
+                deprecated: None,
+                format: ParameterSchemaOrContent::Schema(SchemaObject {
+                    json_schema: schema.into(),
+                    external_docs: None,
+                    example: None,
+                }),
+                example: None,
+                examples: Default::default(),
+                explode: None,
+                extensions: Default::default(),
+            },
+            allow_reserved: false,
+            style: QueryStyle::Form,
+            allow_empty_value: None,
+        }));
+}
+
+/// A filter parsed from the query string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    /// Declared field name
+    pub field: &'static str,
+    /// Comparison operator
+    pub op: FilterOp,
+    /// Values to compare with; several only for [`FilterOp::In`]
+    pub values: Vec<String>,
+}
+
+impl Filter {
+    /// First value of the filter
+    pub fn value(&self) -> &str {
+        self.values.first().map(String::as_str).unwrap_or_default()
+    }
+}
+
+/// A sort key parsed from the `sort` query parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    /// Declared field name
+    pub field: &'static str,
+    /// Sort direction
+    pub direction: SortDirection,
+}
+
+/// Filters and sort requested by the client, read with `ctx.list_query()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+///     let mut sql = String::from("SELECT * FROM orders WHERE 1 = 1");
+///     for filter in ctx.list_query().filters() {
+///         // Field names are declared on the route, so they are safe to interpolate
+///         sql.push_str(&format!(" AND {} {} ?", filter.field, filter.op.as_sql()));
+///     }
+///     ...
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ListQuery {
+    filters: Vec<Filter>,
+    sort: Vec<SortKey>,
+}
+
+impl ListQuery {
+    /// Filters in query string order
+    pub fn filters(&self) -> &[Filter] {
+        &self.filters
+    }
+
+    /// Filters on one field
+    pub fn filters_on<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a Filter> {
+        self.filters
+            .iter()
+            .filter(move |filter| filter.field == field)
+    }
+
+    /// Sort keys in priority order, the default sort if the request has none
+    pub fn sort(&self) -> &[SortKey] {
+        &self.sort
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec() -> FilterSpec {
+        FilterSpec::new()
+            .field("status", [FilterOp::Eq, FilterOp::In])
+            .field("total", [FilterOp::Gte, FilterOp::Lt])
+            .sortable(["created_at", "total"])
+            .default_sort("-created_at")
+    }
+
+    #[test]
+    fn test_parse_filters_and_sort() {
+        let query = spec()
+            .parse(Some(
+                "status[in]=paid,shipped&total[gte]=10&page=2&sort=total,-created_at",
+            ))
+            .unwrap();
+        assert_eq!(
+            query.filters(),
+            [
+                Filter {
+                    field: "status",
+                    op: FilterOp::In,
+                    values: vec!["paid".to_string(), "shipped".to_string()],
+                },
+                Filter {
+                    field: "total",
+                    op: FilterOp::Gte,
+                    values: vec!["10".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            query.sort(),
+            [
+                SortKey {
+                    field: "total",
+                    direction: SortDirection::Asc,
+                },
+                SortKey {
+                    field: "created_at",
+                    direction: SortDirection::Desc,
+                },
+            ]
+        );
+
+        let query = spec().parse(None).unwrap();
+        assert!(query.filters().is_empty());
+        assert_eq!(query.sort()[0].direction, SortDirection::Desc);
+
+        for invalid in ["status[lt]=a", "total[near]=1", "sort=owner"] {
+            assert!(spec().parse(Some(invalid)).is_err(), "{}", invalid);
+        }
+
+        // Undeclared fields are left to the handler, bracketed or not
+        for ignored in ["owner=1", "owner[eq]=1", "address[city]=Paris"] {
+            let query = spec().parse(Some(ignored)).unwrap();
+            assert!(query.filters().is_empty(), "{}", ignored);
+        }
+    }
+
+    #[test]
+    fn test_default_sort_before_sortable() {
+        let spec = FilterSpec::new()
+            .default_sort("-created_at")
+            .sortable(["created_at"]);
+        spec.check_default_sort();
+        assert_eq!(spec.parse(None).unwrap().sort()[0].field, "created_at");
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid default sort `owner`")]
+    fn test_default_sort_not_sortable() {
+        FilterSpec::new()
+            .sortable(["created_at"])
+            .default_sort("owner")
+            .check_default_sort();
+    }
+}
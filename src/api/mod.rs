@@ -1,6 +1,10 @@
 #[allow(clippy::module_inception)]
 pub mod api;
+pub mod filter;
+pub mod pagination;
 pub mod response;
 
 pub use api::*;
-pub use response::{Error, Response};
+pub use filter::{Filter, FilterOp, FilterSpec, ListQuery, SortDirection, SortKey};
+pub use pagination::{Page, Paginate, Pagination, PaginationMode};
+pub use response::{ApiError, Error, ProblemDetails, Reply, Response};
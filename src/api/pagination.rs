@@ -0,0 +1,415 @@
+//! Pagination of list endpoints with offset/limit or opaque cursors.
+
+use axum::http::{HeaderValue, Uri, header};
+use axum::response::IntoResponse;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::api::response::Error;
+use crate::server::params::Query;
+
+/// How a paginated route addresses its pages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaginationMode {
+    /// `?offset=40&limit=20`
+    Offset,
+    /// `?cursor=abc&limit=20`, with cursors chosen by the handler
+    Cursor,
+}
+
+/// Pagination settings of a route, declared with [`Route::paginate()`](crate::server::Route::paginate).
+///
+/// # Example
+///
+/// ```rust
+/// use uncovr::api::Paginate;
+/// use uncovr::server::Route;
+///
+/// let route = Route::get("/users").paginate(Paginate::offset().max_limit(50));
+/// let feed = Route::get("/feed").paginate(Paginate::cursor().default_limit(10));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Paginate {
+    /// Whether pages are addressed by offset or by cursor
+    pub mode: PaginationMode,
+    /// Page size used when the request has no `limit` parameter
+    pub default_limit: u32,
+    /// Largest page size; larger `limit` values are reduced to it
+    pub max_limit: u32,
+}
+
+impl Paginate {
+    /// Offset/limit pagination with the `offset` and `limit` query parameters.
+    pub fn offset() -> Self {
+        Self::new(PaginationMode::Offset)
+    }
+
+    /// Cursor pagination with the `cursor` and `limit` query parameters.
+    pub fn cursor() -> Self {
+        Self::new(PaginationMode::Cursor)
+    }
+
+    fn new(mode: PaginationMode) -> Self {
+        Self {
+            mode,
+            default_limit: 20,
+            max_limit: 100,
+        }
+    }
+
+    /// Sets the page size used when the request has no `limit` parameter (default: 20).
+    pub fn default_limit(mut self, limit: u32) -> Self {
+        self.default_limit = limit.max(1);
+        self.max_limit = self.max_limit.max(self.default_limit);
+        self
+    }
+
+    /// Sets the largest page size a client can request (default: 100).
+    pub fn max_limit(mut self, limit: u32) -> Self {
+        self.max_limit = limit.max(1);
+        self.default_limit = self.default_limit.min(self.max_limit);
+        self
+    }
+
+    /// Reads the page requested by the query string.
+    ///
+    /// Values that do not parse are rejected with a 400 `invalid_query` error; a `limit`
+    /// above the maximum is reduced to it.
+    pub(crate) fn extract(&self, query: &Query, uri: &Uri) -> Result<Pagination, Error> {
+        let limit = match query.get("limit") {
+            Some(limit) => limit
+                .parse::<u32>()
+                .ok()
+                .filter(|limit| *limit > 0)
+                .ok_or_else(|| invalid("limit", "a positive integer"))?
+                .min(self.max_limit),
+            None => self.default_limit,
+        };
+        let offset = match (self.mode, query.get("offset")) {
+            (PaginationMode::Offset, Some(offset)) => offset
+                .parse::<u64>()
+                .map_err(|_| invalid("offset", "a non-negative integer"))?,
+            _ => 0,
+        };
+        let cursor = match self.mode {
+            PaginationMode::Offset => None,
+            PaginationMode::Cursor => query
+                .get("cursor")
+                .filter(|cursor| !cursor.is_empty())
+                .map(str::to_string),
+        };
+
+        Ok(Pagination {
+            mode: self.mode,
+            offset,
+            limit,
+            cursor,
+            uri: uri.clone(),
+        })
+    }
+
+    /// Returns whether `name` is one of the query parameters read by this pagination.
+    pub(crate) fn reads(&self, name: &str) -> bool {
+        match self.mode {
+            PaginationMode::Offset => name == "offset" || name == "limit",
+            PaginationMode::Cursor => name == "cursor" || name == "limit",
+        }
+    }
+}
+
+fn invalid(name: &str, expected: &str) -> Error {
+    Error::bad_request(
+        "invalid_query",
+        format!("Query parameter `{}` must be {}", name, expected),
+    )
+}
+
+/// Page requested by the client, read with `ctx.pagination()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // Route::get("/users").paginate(Paginate::offset())
+/// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+///     let page = ctx.pagination();
+///     let (users, total) = db::list_users(page.offset(), page.limit()).await?;
+///     Ok(page.page(users).total(total))
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Pagination {
+    mode: PaginationMode,
+    offset: u64,
+    limit: u32,
+    cursor: Option<String>,
+    uri: Uri,
+}
+
+impl Pagination {
+    /// Pagination mode of the route
+    pub fn mode(&self) -> PaginationMode {
+        self.mode
+    }
+
+    /// Number of items to skip; always 0 for cursor pagination
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Number of items to return, at most the route's maximum page size
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// Cursor sent by the client, `None` for the first page
+    pub fn cursor(&self) -> Option<&str> {
+        self.cursor.as_deref()
+    }
+
+    /// Builds the response page holding `items`.
+    pub fn page<T>(&self, items: Vec<T>) -> Page<T> {
+        Page {
+            items,
+            limit: self.limit,
+            offset: (self.mode == PaginationMode::Offset).then_some(self.offset),
+            total: None,
+            next: None,
+            prev: None,
+            pagination: Some(self.clone()),
+        }
+    }
+}
+
+/// A page of items returned by a paginated route.
+///
+/// Serializes as `{ "items": [...], "limit": 20, "offset": 40, "total": 95 }` for offset
+/// pagination and `{ "items": [...], "limit": 20, "next": "...", "prev": "..." }` for
+/// cursor pagination. The response also carries an [RFC 8288](https://www.rfc-editor.org/rfc/rfc8288)
+/// `Link` header with the `next`, `prev`, `first` and (when the total is known) `last` pages.
+///
+/// Build it with [`Pagination::page()`] so the links point at the requested URL.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Page<T> {
+    /// Items of the page
+    pub items: Vec<T>,
+    /// Maximum number of items per page
+    pub limit: u32,
+    /// Number of items skipped before this page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Total number of items, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+    /// Cursor of the next page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next: Option<String>,
+    /// Cursor of the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prev: Option<String>,
+    #[serde(skip)]
+    #[schemars(skip)]
+    pagination: Option<Pagination>,
+}
+
+impl<T> Page<T> {
+    /// Sets the total number of items across all pages.
+    pub fn total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Sets the cursor of the next page; leave it unset on the last page.
+    pub fn next_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.next = Some(cursor.into());
+        self
+    }
+
+    /// Sets the cursor of the previous page; leave it unset on the first page.
+    pub fn prev_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.prev = Some(cursor.into());
+        self
+    }
+
+    /// Returns the `Link` header value for the neighbouring pages.
+    pub fn links(&self) -> Option<String> {
+        let pagination = self.pagination.as_ref()?;
+        let uri = &pagination.uri;
+
+        let links = match pagination.mode {
+            PaginationMode::Offset => {
+                let limit = u64::from(self.limit);
+                let offset = self.offset.unwrap_or(0);
+                let end = offset + self.items.len() as u64;
+                let has_next = match self.total {
+                    Some(total) => end < total,
+                    None => self.items.len() as u64 == limit,
+                };
+
+                let mut pages = vec![("first", 0)];
+                if offset > 0 {
+                    pages.push(("prev", offset.saturating_sub(limit)));
+                }
+                if has_next {
+                    pages.push(("next", end));
+                }
+                if let Some(total) = self.total {
+                    pages.push(("last", total.saturating_sub(1) / limit * limit));
+                }
+                pages
+                    .into_iter()
+                    .map(|(rel, offset)| {
+                        link(uri, rel, "offset", Some(&offset.to_string()), self.limit)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            PaginationMode::Cursor => {
+                let mut links = vec![link(uri, "first", "cursor", None, self.limit)];
+                if let Some(prev) = &self.prev {
+                    links.push(link(uri, "prev", "cursor", Some(prev), self.limit));
+                }
+                if let Some(next) = &self.next {
+                    links.push(link(uri, "next", "cursor", Some(next), self.limit));
+                }
+                links
+            }
+        };
+        Some(links.join(", "))
+    }
+}
+
+/// Formats one link to the request URL with the page parameters replaced.
+fn link(uri: &Uri, rel: &str, key: &str, value: Option<&str>, limit: u32) -> String {
+    let mut query: Vec<(String, String)> = uri
+        .query()
+        .and_then(|query| serde_urlencoded::from_str(query).ok())
+        .unwrap_or_default();
+    query.retain(|(name, _)| name != key && name != "limit");
+    if let Some(value) = value {
+        query.push((key.to_string(), value.to_string()));
+    }
+    query.push(("limit".to_string(), limit.to_string()));
+
+    let query = serde_urlencoded::to_string(&query).unwrap_or_default();
+    format!("<{}?{}>; rel=\"{}\"", uri.path(), query, rel)
+}
+
+impl<T: Serialize> IntoResponse for Page<T> {
+    fn into_response(self) -> axum::response::Response {
+        let links = self.links();
+        let mut response = axum::Json(self).into_response();
+        if let Some(value) = links.and_then(|links| HeaderValue::try_from(links).ok()) {
+            response.headers_mut().insert(header::LINK, value);
+        }
+        response
+    }
+}
+
+impl<T> aide::OperationOutput for Page<T>
+where
+    T: JsonSchema + Serialize,
+{
+    type Inner = Self;
+
+    fn operation_response(
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) -> Option<aide::openapi::Response> {
+        <axum::Json<Self> as aide::OperationOutput>::operation_response(ctx, operation)
+            .map(with_link_header)
+    }
+
+    fn inferred_responses(
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) -> Vec<(Option<u16>, aide::openapi::Response)> {
+        <axum::Json<Self> as aide::OperationOutput>::inferred_responses(ctx, operation)
+            .into_iter()
+            .map(|(status, response)| (status, with_link_header(response)))
+            .collect()
+    }
+}
+
+/// Documents the `Link` header on a page response.
+fn with_link_header(mut response: aide::openapi::Response) -> aide::openapi::Response {
+    let schema = schemars::schema::SchemaObject {
+        instance_type: Some(schemars::schema::InstanceType::String.into()),
+        ..Default::default()
+    };
+    let link = aide::openapi::Header {
+        description: Some("Links to the neighbouring pages (RFC 8288)".to_string()),
+        style: Default::default(),
+        required: false,
+        deprecated: None,
+        format: aide::openapi::ParameterSchemaOrContent::Schema(aide::openapi::SchemaObject {
+            json_schema: schema.into(),
+            external_docs: None,
+            example: None,
+        }),
+        example: None,
+        examples: Default::default(),
+        extensions: Default::default(),
+    };
+    response
+        .headers
+        .insert("Link".to_string(), aide::openapi::ReferenceOr::Item(link));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn pagination(paginate: Paginate, uri: &'static str) -> Pagination {
+        let uri = Uri::from_static(uri);
+        let params: HashMap<String, String> = uri
+            .query()
+            .map(|query| serde_urlencoded::from_str(query).unwrap())
+            .unwrap_or_default();
+        paginate.extract(&Query::new(params), &uri).unwrap()
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let extract = |uri: &'static str| {
+            let uri = Uri::from_static(uri);
+            let query = Query::from_query_string(uri.query().unwrap_or_default());
+            Paginate::offset().extract(&query, &uri)
+        };
+
+        for uri in [
+            "/users?offset=abc",
+            "/users?offset=-1",
+            "/users?limit=-5",
+            "/users?limit=0",
+        ] {
+            let error = extract(uri).unwrap_err();
+            assert_eq!(error.status_code(), axum::http::StatusCode::BAD_REQUEST);
+        }
+        assert_eq!(extract("/users?limit=500").unwrap().limit(), 100);
+    }
+
+    #[test]
+    fn test_offset_links() {
+        let request = pagination(Paginate::offset(), "/users?q=a%20b&offset=20&limit=500");
+        assert_eq!(request.limit(), 100);
+
+        let request = pagination(Paginate::offset(), "/users?q=a%20b&offset=20&limit=10");
+        let page = request.page(vec![0; 10]).total(45);
+        assert_eq!(
+            page.links().unwrap(),
+            "</users?q=a+b&offset=0&limit=10>; rel=\"first\", \
+             </users?q=a+b&offset=10&limit=10>; rel=\"prev\", \
+             </users?q=a+b&offset=30&limit=10>; rel=\"next\", \
+             </users?q=a+b&offset=40&limit=10>; rel=\"last\""
+        );
+
+        // Without a total, a short page is the last one
+        let request = pagination(Paginate::offset(), "/users");
+        let page = request.page(vec![0; 3]);
+        assert_eq!(
+            page.links().unwrap(),
+            "</users?offset=0&limit=20>; rel=\"first\""
+        );
+    }
+}
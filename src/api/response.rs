@@ -1,6 +1,6 @@
 //! Type-safe HTTP response types for uncovr handlers with automatic OpenAPI schema generation.
 
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, HeaderName, HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Response as AxumResponse};
 use serde::Serialize;
 use std::fmt;
@@ -63,17 +63,269 @@ where
     type Inner = T;
 
     fn operation_response(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Option<aide::openapi::Response> {
-        None
+        <axum::Json<T> as aide::OperationOutput>::operation_response(ctx, operation)
     }
 
+    /// Documents 200 and 201 with the schema of `T`, and 204 without a body.
     fn inferred_responses(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Vec<(Option<u16>, aide::openapi::Response)> {
-        Vec::new()
+        let Some(json) = Self::operation_response(ctx, operation) else {
+            return Vec::new();
+        };
+
+        [StatusCode::OK, StatusCode::CREATED, StatusCode::NO_CONTENT]
+            .into_iter()
+            .map(|status| {
+                let mut response = if status == StatusCode::NO_CONTENT {
+                    aide::openapi::Response::default()
+                } else {
+                    json.clone()
+                };
+                response.description = status.canonical_reason().unwrap_or_default().to_string();
+                (Some(status.as_u16()), response)
+            })
+            .collect()
+    }
+}
+
+/// Success response with a custom status code and headers.
+///
+/// Covers what [`Response`] cannot express: any status code (202 Accepted,
+/// 206 Partial Content, redirects, ...) and response headers such as `Location`,
+/// `Set-Cookie`, `Cache-Control` and `ETag`. The body, if any, is serialized as JSON.
+///
+/// Documented as a 200 response with the schema of `T`; use
+/// [`Meta::success_status()`](crate::server::Meta::success_status) and
+/// [`Meta::response_header()`](crate::server::Meta::response_header) to document
+/// the actual status and headers.
+///
+/// Invalid header names or values turn the response into a 500 error.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// use uncovr::prelude::*;
+///
+/// Reply::created(user).location(format!("/users/{}", user.id))
+/// Reply::accepted(job).header("retry-after", "5")
+/// Reply::ok(article).etag("v42").cache_control("max-age=60")
+/// Reply::see_other("/login")
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reply<T> {
+    status: StatusCode,
+    body: Option<T>,
+    headers: HeaderMap,
+    error: Option<String>,
+}
+
+impl<T> Reply<T> {
+    /// Creates a response with a status code and a JSON body.
+    pub fn new(status: StatusCode, body: T) -> Self {
+        Self {
+            status,
+            body: Some(body),
+            headers: HeaderMap::new(),
+            error: None,
+        }
+    }
+
+    /// Creates a response with a status code and no body.
+    pub fn empty(status: StatusCode) -> Self {
+        Self {
+            status,
+            body: None,
+            headers: HeaderMap::new(),
+            error: None,
+        }
+    }
+
+    /// Creates a 200 OK response.
+    pub fn ok(body: T) -> Self {
+        Self::new(StatusCode::OK, body)
+    }
+
+    /// Creates a 201 Created response.
+    pub fn created(body: T) -> Self {
+        Self::new(StatusCode::CREATED, body)
+    }
+
+    /// Creates a 202 Accepted response.
+    pub fn accepted(body: T) -> Self {
+        Self::new(StatusCode::ACCEPTED, body)
+    }
+
+    /// Creates a 206 Partial Content response.
+    pub fn partial_content(body: T) -> Self {
+        Self::new(StatusCode::PARTIAL_CONTENT, body)
+    }
+
+    /// Creates a 204 No Content response.
+    pub fn no_content() -> Self {
+        Self::empty(StatusCode::NO_CONTENT)
+    }
+
+    /// Creates a 303 See Other redirect, the usual redirect after a form submission.
+    pub fn see_other(location: impl AsRef<str>) -> Self {
+        Self::empty(StatusCode::SEE_OTHER).location(location)
+    }
+
+    /// Creates a 307 Temporary Redirect, which preserves the request method and body.
+    pub fn temporary_redirect(location: impl AsRef<str>) -> Self {
+        Self::empty(StatusCode::TEMPORARY_REDIRECT).location(location)
+    }
+
+    /// Creates a 308 Permanent Redirect, which preserves the request method and body.
+    pub fn permanent_redirect(location: impl AsRef<str>) -> Self {
+        Self::empty(StatusCode::PERMANENT_REDIRECT).location(location)
+    }
+
+    /// Sets the status code.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets a header, replacing any previous value.
+    pub fn header(self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.put_header(name.as_ref(), value.as_ref(), false)
+    }
+
+    /// Adds a header value, keeping previous values of the same header.
+    pub fn append_header(self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        self.put_header(name.as_ref(), value.as_ref(), true)
+    }
+
+    /// Adds a `Set-Cookie` header, such as `"theme=dark; Path=/; Max-Age=3600"`.
+    pub fn cookie(self, cookie: impl AsRef<str>) -> Self {
+        self.append_header(header::SET_COOKIE, cookie)
+    }
+
+    /// Adds a `Set-Cookie` header for a [`Cookie`](crate::server::cookies::Cookie).
+    ///
+    /// Accepts a `Cookie`, a `CookieBuilder` or a `(name, value)` pair. Signed and
+    /// private cookies are created with `ctx.cookies().sign()` and `.encrypt()`.
+    pub fn set_cookie(self, cookie: impl Into<cookie::Cookie<'static>>) -> Self {
+        let cookie = cookie.into();
+        self.append_header(header::SET_COOKIE, cookie.encoded().to_string())
+    }
+
+    /// Adds a `Set-Cookie` header that removes a cookie from the client.
+    ///
+    /// The cookie must have the same `Path` and `Domain` as when it was set.
+    pub fn remove_cookie(self, cookie: impl Into<cookie::Cookie<'static>>) -> Self {
+        let mut cookie = cookie.into();
+        cookie.make_removal();
+        self.set_cookie(cookie)
+    }
+
+    /// Sets the `Location` header.
+    pub fn location(self, location: impl AsRef<str>) -> Self {
+        self.header(header::LOCATION, location)
+    }
+
+    /// Sets the `Cache-Control` header, such as `"no-store"` or `"max-age=60"`.
+    pub fn cache_control(self, directives: impl AsRef<str>) -> Self {
+        self.header(header::CACHE_CONTROL, directives)
+    }
+
+    /// Sets the `ETag` header.
+    ///
+    /// Unquoted tags are quoted, so `etag("v42")` sends `"v42"`; weak tags such as
+    /// `W/"v42"` are sent unchanged.
+    pub fn etag(self, tag: impl AsRef<str>) -> Self {
+        let tag = tag.as_ref();
+        if tag.starts_with('"') || tag.starts_with("W/") {
+            self.header(header::ETAG, tag)
+        } else {
+            self.header(header::ETAG, format!("\"{}\"", tag))
+        }
+    }
+
+    /// Returns the status code.
+    pub fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the headers set so far.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    fn put_header(mut self, name: &str, value: &str, append: bool) -> Self {
+        let parsed = HeaderName::try_from(name)
+            .map_err(|err| err.to_string())
+            .and_then(|name| {
+                HeaderValue::try_from(value)
+                    .map(|value| (name, value))
+                    .map_err(|err| err.to_string())
+            });
+
+        match parsed {
+            Ok((name, value)) if append => {
+                self.headers.append(name, value);
+            }
+            Ok((name, value)) => {
+                self.headers.insert(name, value);
+            }
+            Err(err) => {
+                self.error
+                    .get_or_insert_with(|| format!("Invalid response header `{}`: {}", name, err));
+            }
+        }
+        self
+    }
+}
+
+impl<T> From<Response<T>> for Reply<T> {
+    fn from(response: Response<T>) -> Self {
+        match response {
+            Response::Ok(data) => Self::ok(data),
+            Response::Created(data) => Self::created(data),
+            Response::NoContent => Self::no_content(),
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for Reply<T> {
+    fn into_response(self) -> AxumResponse {
+        if let Some(error) = self.error {
+            tracing::error!("{}", error);
+            return Error::internal("invalid_response", "Internal server error").into_response();
+        }
+
+        let mut response = match self.body {
+            Some(body) => (self.status, axum::Json(body)).into_response(),
+            None => self.status.into_response(),
+        };
+        response.headers_mut().extend(self.headers);
+        response
+    }
+}
+
+impl<T> aide::OperationOutput for Reply<T>
+where
+    T: schemars::JsonSchema + Serialize,
+{
+    type Inner = T;
+
+    fn operation_response(
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) -> Option<aide::openapi::Response> {
+        <axum::Json<T> as aide::OperationOutput>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
+    ) -> Vec<(Option<u16>, aide::openapi::Response)> {
+        <axum::Json<T> as aide::OperationOutput>::inferred_responses(ctx, operation)
     }
 }
 
@@ -90,7 +342,7 @@ where
 /// - **`serde_json::Error`** → 400 Bad Request
 /// - **`ParseIntError`**, **`ParseFloatError`**, **`ParseBoolError`** → 400 Bad Request
 /// - **`std::io::Error`** → 500 Internal Server Error (with error logging)
-/// - **`validator::ValidationErrors`** → 422 Unprocessable Entity (with field details)
+/// - **`validator::ValidationErrors`** → 422 Unprocessable Entity (with field details, `validation` feature)
 ///
 /// # Examples
 ///
@@ -141,6 +393,20 @@ pub enum Error {
         #[serde(skip_serializing_if = "Option::is_none")]
         details: Option<serde_json::Value>,
     },
+    /// 405 Method Not Allowed - Route does not handle the request method
+    MethodNotAllowed {
+        code: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        details: Option<serde_json::Value>,
+    },
+    /// 406 Not Acceptable - No acceptable response format
+    NotAcceptable {
+        code: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        details: Option<serde_json::Value>,
+    },
     /// 409 Conflict - Resource conflict
     Conflict {
         code: String,
@@ -148,6 +414,20 @@ pub enum Error {
         #[serde(skip_serializing_if = "Option::is_none")]
         details: Option<serde_json::Value>,
     },
+    /// 413 Payload Too Large - Request body or upload exceeds a size limit
+    PayloadTooLarge {
+        code: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        details: Option<serde_json::Value>,
+    },
+    /// 415 Unsupported Media Type - Request body encoding not accepted
+    UnsupportedMediaType {
+        code: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        details: Option<serde_json::Value>,
+    },
     /// 422 Unprocessable Entity - Validation failed
     UnprocessableEntity {
         code: String,
@@ -221,6 +501,24 @@ impl Error {
         }
     }
 
+    /// Creates a 405 Method Not Allowed error.
+    pub fn method_not_allowed(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::MethodNotAllowed {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Creates a 406 Not Acceptable error.
+    pub fn not_acceptable(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::NotAcceptable {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
     /// Creates a 409 Conflict error.
     pub fn conflict(code: impl Into<String>, message: impl Into<String>) -> Self {
         Self::Conflict {
@@ -230,6 +528,24 @@ impl Error {
         }
     }
 
+    /// Creates a 413 Payload Too Large error.
+    pub fn payload_too_large(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::PayloadTooLarge {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Creates a 415 Unsupported Media Type error.
+    pub fn unsupported_media_type(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::UnsupportedMediaType {
+            code: code.into(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
     /// Creates a 422 Unprocessable Entity error.
     pub fn unprocessable(code: impl Into<String>, message: impl Into<String>) -> Self {
         Self::UnprocessableEntity {
@@ -270,6 +586,96 @@ impl Error {
         }
     }
 
+    /// Status codes of all error variants, in declaration order.
+    pub(crate) const STATUS_CODES: [StatusCode; 12] = [
+        StatusCode::BAD_REQUEST,
+        StatusCode::UNAUTHORIZED,
+        StatusCode::FORBIDDEN,
+        StatusCode::NOT_FOUND,
+        StatusCode::METHOD_NOT_ALLOWED,
+        StatusCode::NOT_ACCEPTABLE,
+        StatusCode::CONFLICT,
+        StatusCode::PAYLOAD_TOO_LARGE,
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        StatusCode::INTERNAL_SERVER_ERROR,
+        StatusCode::SERVICE_UNAVAILABLE,
+    ];
+
+    /// Builds the OpenAPI response documenting an error body under `status`.
+    pub(crate) fn openapi_response(
+        ctx: &mut aide::r#gen::GenContext,
+        status: StatusCode,
+    ) -> aide::openapi::Response {
+        error_response::<Self>(ctx, status)
+    }
+
+    /// Returns the code, message and details shared by all variants.
+    fn fields(&self) -> (&str, &str, Option<&serde_json::Value>) {
+        match self {
+            Error::BadRequest {
+                code,
+                message,
+                details,
+            }
+            | Error::Unauthorized {
+                code,
+                message,
+                details,
+            }
+            | Error::Forbidden {
+                code,
+                message,
+                details,
+            }
+            | Error::NotFound {
+                code,
+                message,
+                details,
+            }
+            | Error::MethodNotAllowed {
+                code,
+                message,
+                details,
+            }
+            | Error::NotAcceptable {
+                code,
+                message,
+                details,
+            }
+            | Error::Conflict {
+                code,
+                message,
+                details,
+            }
+            | Error::PayloadTooLarge {
+                code,
+                message,
+                details,
+            }
+            | Error::UnsupportedMediaType {
+                code,
+                message,
+                details,
+            }
+            | Error::UnprocessableEntity {
+                code,
+                message,
+                details,
+            }
+            | Error::InternalError {
+                code,
+                message,
+                details,
+            }
+            | Error::ServiceUnavailable {
+                code,
+                message,
+                details,
+            } => (code, message, details.as_ref()),
+        }
+    }
+
     /// Returns the HTTP status code for this error.
     pub fn status_code(&self) -> StatusCode {
         match self {
@@ -277,7 +683,11 @@ impl Error {
             Error::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
             Error::Forbidden { .. } => StatusCode::FORBIDDEN,
             Error::NotFound { .. } => StatusCode::NOT_FOUND,
+            Error::MethodNotAllowed { .. } => StatusCode::METHOD_NOT_ALLOWED,
+            Error::NotAcceptable { .. } => StatusCode::NOT_ACCEPTABLE,
             Error::Conflict { .. } => StatusCode::CONFLICT,
+            Error::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Error::UnsupportedMediaType { .. } => StatusCode::UNSUPPORTED_MEDIA_TYPE,
             Error::UnprocessableEntity { .. } => StatusCode::UNPROCESSABLE_ENTITY,
             Error::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Error::ServiceUnavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
@@ -300,9 +710,21 @@ impl fmt::Display for Error {
             Error::NotFound { code, message, .. } => {
                 write!(f, "Not Found [{}]: {}", code, message)
             }
+            Error::MethodNotAllowed { code, message, .. } => {
+                write!(f, "Method Not Allowed [{}]: {}", code, message)
+            }
+            Error::NotAcceptable { code, message, .. } => {
+                write!(f, "Not Acceptable [{}]: {}", code, message)
+            }
             Error::Conflict { code, message, .. } => {
                 write!(f, "Conflict [{}]: {}", code, message)
             }
+            Error::PayloadTooLarge { code, message, .. } => {
+                write!(f, "Payload Too Large [{}]: {}", code, message)
+            }
+            Error::UnsupportedMediaType { code, message, .. } => {
+                write!(f, "Unsupported Media Type [{}]: {}", code, message)
+            }
             Error::UnprocessableEntity { code, message, .. } => {
                 write!(f, "Unprocessable Entity [{}]: {}", code, message)
             }
@@ -320,8 +742,7 @@ impl std::error::Error for Error {}
 
 impl IntoResponse for Error {
     fn into_response(self) -> AxumResponse {
-        let status = self.status_code();
-        (status, axum::Json(self)).into_response()
+        self.into_api_response()
     }
 }
 
@@ -329,20 +750,289 @@ impl aide::OperationOutput for Error {
     type Inner = Self;
 
     fn operation_response(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Option<aide::openapi::Response> {
-        None
+        <axum::Json<Self> as aide::OperationOutput>::operation_response(ctx, operation)
     }
 
+    /// Documents the error schema under the status code of every variant.
     fn inferred_responses(
-        _ctx: &mut aide::r#gen::GenContext,
+        ctx: &mut aide::r#gen::GenContext,
         _operation: &mut aide::openapi::Operation,
     ) -> Vec<(Option<u16>, aide::openapi::Response)> {
-        Vec::new()
+        Self::openapi_responses(ctx)
+    }
+}
+
+/// Application error type that can be returned from handlers in place of [`Error`].
+///
+/// The response body is the type's `Serialize` output and is documented with its
+/// `JsonSchema`, so the JSON layout is up to the implementation. `code()`, `message()`
+/// and `details()` describe the error independently of that layout.
+///
+/// uncovr's automatic conversions (from `ParamError`, `serde_json::Error`,
+/// `ValidationErrors` and others) produce an [`Error`], which is turned into the
+/// application type through the `From<Error>` bound.
+///
+/// Use the type with [`HandlerResult<T, E>`], or call
+/// [`impl_api_error!`](crate::impl_api_error) to return it directly from handlers.
+///
+/// # Example
+///
+/// ```rust
+/// use uncovr::api::response::ApiError;
+/// use uncovr::prelude::*;
+///
+/// #[derive(Serialize, schemars::JsonSchema)]
+/// #[serde(tag = "kind", rename_all = "snake_case")]
+/// enum ShopError {
+///     OutOfStock { sku: String },
+///     RateLimited,
+///     Invalid { message: String },
+/// }
+///
+/// impl From<Error> for ShopError {
+///     fn from(error: Error) -> Self {
+///         ShopError::Invalid { message: error.to_string() }
+///     }
+/// }
+///
+/// impl ApiError for ShopError {
+///     fn status_code(&self) -> StatusCode {
+///         match self {
+///             ShopError::OutOfStock { .. } => StatusCode::CONFLICT,
+///             ShopError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+///             ShopError::Invalid { .. } => StatusCode::BAD_REQUEST,
+///         }
+///     }
+///
+///     fn code(&self) -> &str {
+///         match self {
+///             ShopError::OutOfStock { .. } => "out_of_stock",
+///             ShopError::RateLimited => "rate_limited",
+///             ShopError::Invalid { .. } => "invalid_request",
+///         }
+///     }
+///
+///     fn message(&self) -> String {
+///         match self {
+///             ShopError::OutOfStock { sku } => format!("{} is out of stock", sku),
+///             ShopError::RateLimited => "Too many requests".to_string(),
+///             ShopError::Invalid { message } => message.clone(),
+///         }
+///     }
+///
+///     fn statuses() -> &'static [StatusCode] {
+///         &[StatusCode::BAD_REQUEST, StatusCode::CONFLICT, StatusCode::TOO_MANY_REQUESTS]
+///     }
+/// }
+///
+/// uncovr::impl_api_error!(ShopError);
+/// ```
+pub trait ApiError:
+    From<Error> + Serialize + schemars::JsonSchema + Send + Sized + 'static
+{
+    /// HTTP status code of the response.
+    fn status_code(&self) -> StatusCode;
+
+    /// Stable, machine-readable error code such as `"out_of_stock"`.
+    fn code(&self) -> &str;
+
+    /// Human-readable error message.
+    fn message(&self) -> String;
+
+    /// Additional structured information about the error.
+    fn details(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Status codes this type can produce, documented in the OpenAPI specification.
+    fn statuses() -> &'static [StatusCode];
+
+    /// Renders the error as a JSON response with its status code.
+    fn into_api_response(self) -> AxumResponse {
+        let status = self.status_code();
+        let info = ErrorInfo {
+            code: self.code().to_string(),
+            message: self.message(),
+            details: self.details(),
+        };
+        let mut response = (status, axum::Json(self)).into_response();
+        response.extensions_mut().insert(info);
+        response
+    }
+
+    /// Documents the error schema under each of [`ApiError::statuses()`].
+    fn openapi_responses(
+        ctx: &mut aide::r#gen::GenContext,
+    ) -> Vec<(Option<u16>, aide::openapi::Response)> {
+        Self::statuses()
+            .iter()
+            .map(|status| (Some(status.as_u16()), error_response::<Self>(ctx, *status)))
+            .collect()
+    }
+}
+
+impl ApiError for Error {
+    fn status_code(&self) -> StatusCode {
+        Error::status_code(self)
+    }
+
+    fn code(&self) -> &str {
+        self.fields().0
+    }
+
+    fn message(&self) -> String {
+        self.fields().1.to_string()
+    }
+
+    fn details(&self) -> Option<serde_json::Value> {
+        self.fields().2.cloned()
+    }
+
+    fn statuses() -> &'static [StatusCode] {
+        &Self::STATUS_CODES
     }
 }
 
+/// Code, message and details of an error response.
+///
+/// Stored in the response extensions by [`ApiError::into_api_response()`] so
+/// middleware can re-render the error, for example as problem details.
+#[derive(Debug, Clone)]
+pub(crate) struct ErrorInfo {
+    code: String,
+    message: String,
+    details: Option<serde_json::Value>,
+}
+
+/// RFC 9457 problem details body.
+///
+/// Rendered with `Content-Type: application/problem+json` for every error when
+/// problem details are enabled with
+/// [`ServerBuilder::with_problem_details()`](crate::server::ServerBuilder::with_problem_details)
+/// or [`App::with_problem_details()`](crate::config::App::with_problem_details).
+/// The error code is kept in the `code` extension member, and object details are
+/// merged into the extension members.
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type; `about:blank` when the status says it all
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Identifier of this occurrence, the request ID when available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension members
+    #[serde(flatten)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ProblemDetails {
+    /// Media type of problem details responses.
+    pub const MEDIA_TYPE: &'static str = "application/problem+json";
+
+    /// Creates problem details for a status code with the `about:blank` type.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or_default().to_string(),
+            status: status.as_u16(),
+            detail: None,
+            instance: None,
+            extensions: serde_json::Map::new(),
+        }
+    }
+
+    fn from_error(status: StatusCode, info: ErrorInfo, instance: Option<String>) -> Self {
+        let mut problem = Self::new(status);
+        problem.detail = Some(info.message);
+        problem.instance = instance;
+        problem
+            .extensions
+            .insert("code".to_string(), serde_json::Value::String(info.code));
+
+        match info.details {
+            Some(serde_json::Value::Object(details)) => {
+                for (name, value) in details {
+                    if !matches!(
+                        name.as_str(),
+                        "type" | "title" | "status" | "detail" | "instance" | "code"
+                    ) {
+                        problem.extensions.insert(name, value);
+                    }
+                }
+            }
+            Some(details) => {
+                problem.extensions.insert("details".to_string(), details);
+            }
+            None => {}
+        }
+        problem
+    }
+}
+
+impl IntoResponse for ProblemDetails {
+    fn into_response(self) -> AxumResponse {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, axum::Json(self)).into_response();
+        response.headers_mut().insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static(Self::MEDIA_TYPE),
+        );
+        response
+    }
+}
+
+/// Re-renders an error response as problem details, keeping its status and headers.
+///
+/// Responses not produced by [`ApiError::into_api_response()`] are returned unchanged.
+/// The `instance` member is filled from the [`RequestId`](crate::middleware::RequestId)
+/// middleware when it ran inside this conversion.
+pub(crate) fn into_problem_details(mut response: AxumResponse) -> AxumResponse {
+    let Some(info) = response.extensions_mut().remove::<ErrorInfo>() else {
+        return response;
+    };
+    let instance = response
+        .extensions()
+        .get::<crate::middleware::RequestIdValue>()
+        .map(|id| id.0.clone());
+
+    let problem = ProblemDetails::from_error(response.status(), info, instance);
+    let Ok(body) = serde_json::to_vec(&problem) else {
+        return response;
+    };
+
+    let headers = response.headers_mut();
+    headers.remove(axum::http::header::CONTENT_LENGTH);
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        axum::http::HeaderValue::from_static(ProblemDetails::MEDIA_TYPE),
+    );
+    *response.body_mut() = axum::body::Body::from(body);
+    response
+}
+
+/// Builds the OpenAPI response documenting an error type's schema under `status`.
+fn error_response<E: schemars::JsonSchema>(
+    ctx: &mut aide::r#gen::GenContext,
+    status: StatusCode,
+) -> aide::openapi::Response {
+    let mut operation = aide::openapi::Operation::default();
+    let mut response =
+        <axum::Json<E> as aide::OperationOutput>::operation_response(ctx, &mut operation)
+            .unwrap_or_default();
+    response.description = status.canonical_reason().unwrap_or_default().to_string();
+    response
+}
+
 /// Validated JSON wrapper for handler responses.
 ///
 /// This is a newtype wrapper around `Json<T>` that implements `aide::OperationOutput`
@@ -391,17 +1081,17 @@ where
     type Inner = T;
 
     fn operation_response(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Option<aide::openapi::Response> {
-        None
+        <axum::Json<T> as aide::OperationOutput>::operation_response(ctx, operation)
     }
 
     fn inferred_responses(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Vec<(Option<u16>, aide::openapi::Response)> {
-        Vec::new()
+        <axum::Json<T> as aide::OperationOutput>::inferred_responses(ctx, operation)
     }
 }
 
@@ -434,66 +1124,83 @@ where
 ///     }
 /// }
 /// ```
-pub struct HandlerResult<T>(Result<JsonResponse<T>, Error>);
+pub struct HandlerResult<T, E = Error>(Result<JsonResponse<T>, E>);
 
 // Allow using ? operator by implementing From for common error types
-impl<T> From<Error> for HandlerResult<T> {
-    fn from(error: Error) -> Self {
+impl<T, E: ApiError> From<E> for HandlerResult<T, E> {
+    fn from(error: E) -> Self {
         Self(Err(error))
     }
 }
 
-impl<T> From<validator::ValidationErrors> for HandlerResult<T> {
+impl<T, E: ApiError> From<crate::server::params::ParamError> for HandlerResult<T, E> {
+    fn from(err: crate::server::params::ParamError) -> Self {
+        Self(Err(E::from(Error::from(err))))
+    }
+}
+
+impl<T, E: ApiError> From<serde_json::Error> for HandlerResult<T, E> {
+    fn from(err: serde_json::Error) -> Self {
+        Self(Err(E::from(Error::from(err))))
+    }
+}
+
+#[cfg(feature = "validation")]
+impl<T, E: ApiError> From<validator::ValidationErrors> for HandlerResult<T, E> {
     fn from(errors: validator::ValidationErrors) -> Self {
-        Self(Err(Error::from(errors)))
+        Self(Err(E::from(Error::from(errors))))
     }
 }
 
 // Make Ok(value.into()) work
-impl<T> From<Result<JsonResponse<T>, Error>> for HandlerResult<T> {
-    fn from(result: Result<JsonResponse<T>, Error>) -> Self {
+impl<T, E> From<Result<JsonResponse<T>, E>> for HandlerResult<T, E> {
+    fn from(result: Result<JsonResponse<T>, E>) -> Self {
         Self(result)
     }
 }
 
-impl<T> IntoResponse for HandlerResult<T>
+impl<T, E> IntoResponse for HandlerResult<T, E>
 where
     T: Serialize,
+    E: ApiError,
 {
     fn into_response(self) -> AxumResponse {
         match self.0 {
             Ok(json_response) => json_response.into_response(),
-            Err(err) => err.into_response(),
+            Err(err) => err.into_api_response(),
         }
     }
 }
 
-impl<T> aide::OperationOutput for HandlerResult<T>
+impl<T, E> aide::OperationOutput for HandlerResult<T, E>
 where
     T: schemars::JsonSchema + Serialize,
+    E: ApiError,
 {
     type Inner = T;
 
     fn operation_response(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Option<aide::openapi::Response> {
-        None
+        JsonResponse::<T>::operation_response(ctx, operation)
     }
 
     fn inferred_responses(
-        _ctx: &mut aide::r#gen::GenContext,
-        _operation: &mut aide::openapi::Operation,
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut aide::openapi::Operation,
     ) -> Vec<(Option<u16>, aide::openapi::Response)> {
-        Vec::new()
+        let mut responses = JsonResponse::<T>::inferred_responses(ctx, operation);
+        responses.extend(E::openapi_responses(ctx));
+        responses
     }
 }
 
-impl<T> HandlerResult<T> {
-    /// Helper to convert from Result<T, E> where E implements Into<Error>
-    pub fn from_result<E>(result: Result<T, E>) -> Self
+impl<T, E: ApiError> HandlerResult<T, E> {
+    /// Helper to convert from Result<T, X> where X implements Into<E>
+    pub fn from_result<X>(result: Result<T, X>) -> Self
     where
-        E: Into<Error>,
+        X: Into<E>,
     {
         Self(result.map(JsonResponse).map_err(|e| e.into()))
     }
@@ -538,22 +1245,11 @@ impl From<std::str::ParseBoolError> for Error {
     }
 }
 
+#[cfg(feature = "validation")]
 impl From<validator::ValidationErrors> for Error {
     fn from(errors: validator::ValidationErrors) -> Self {
-        use std::collections::HashMap;
-
-        let mut field_errors = HashMap::new();
-
-        for (field, errors) in errors.field_errors() {
-            let messages: Vec<String> = errors
-                .iter()
-                .filter_map(|e| e.message.as_ref().map(|m| m.to_string()))
-                .collect();
-
-            if !messages.is_empty() {
-                field_errors.insert(field.to_string(), messages);
-            }
-        }
+        let mut field_errors = std::collections::BTreeMap::new();
+        collect_validation_errors(&errors, "", &mut field_errors);
 
         Error::unprocessable_with_details(
             "validation_failed",
@@ -562,3 +1258,43 @@ impl From<validator::ValidationErrors> for Error {
         )
     }
 }
+
+/// Flattens validation errors into messages keyed by field path.
+///
+/// Nested fields are joined with dots and list items are indexed, as in
+/// `items[0].name`. Struct-level errors use the struct's path, or `__all__` at the top
+/// level. Errors without a message are reported by their validator code.
+#[cfg(feature = "validation")]
+fn collect_validation_errors(
+    errors: &validator::ValidationErrors,
+    prefix: &str,
+    out: &mut std::collections::BTreeMap<String, Vec<String>>,
+) {
+    use validator::ValidationErrorsKind;
+
+    for (field, kind) in errors.errors() {
+        let path = match (prefix, *field) {
+            ("", field) => field.to_string(),
+            (prefix, "__all__") => prefix.to_string(),
+            (prefix, field) => format!("{}.{}", prefix, field),
+        };
+
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                let messages = errors.iter().map(|error| match &error.message {
+                    Some(message) => message.to_string(),
+                    None => format!("Failed {} validation", error.code),
+                });
+                out.entry(path).or_default().extend(messages);
+            }
+            ValidationErrorsKind::Struct(errors) => {
+                collect_validation_errors(errors, &path, out);
+            }
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_validation_errors(errors, &format!("{}[{}]", path, index), out);
+                }
+            }
+        }
+    }
+}
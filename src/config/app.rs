@@ -42,6 +42,15 @@ pub struct App {
 
     /// Server URLs
     pub servers: Vec<Server>,
+
+    /// Render errors as RFC 9457 problem details (default: false)
+    #[serde(default)]
+    pub problem_details: bool,
+
+    /// Secret used to derive the key for signed and private cookies
+    #[cfg(feature = "secure-cookies")]
+    #[serde(default, skip_serializing)]
+    pub cookie_secret: Option<String>,
 }
 
 /// Server configuration
@@ -83,6 +92,9 @@ impl App {
             docs_path: "/docs".to_string(),
             spec_path: "/api.json".to_string(),
             servers: vec![],
+            problem_details: false,
+            #[cfg(feature = "secure-cookies")]
+            cookie_secret: None,
         }
     }
 
@@ -119,6 +131,24 @@ impl App {
         self
     }
 
+    /// Render errors as RFC 9457 `application/problem+json` responses
+    ///
+    /// See [`ServerBuilder::with_problem_details()`](crate::server::ServerBuilder::with_problem_details).
+    pub fn with_problem_details(mut self, enable: bool) -> Self {
+        self.problem_details = enable;
+        self
+    }
+
+    /// Set the secret for signed and private cookies
+    ///
+    /// The secret must be at least 32 bytes of random data; the cookie key is
+    /// derived from it. See [`ServerBuilder::with_cookie_key()`](crate::server::ServerBuilder::with_cookie_key).
+    #[cfg(feature = "secure-cookies")]
+    pub fn with_cookie_secret(mut self, secret: impl Into<String>) -> Self {
+        self.cookie_secret = Some(secret.into());
+        self
+    }
+
     /// Add a server URL
     pub fn server(mut self, url: impl Into<String>, description: impl Into<String>) -> Self {
         self.servers.push(Server {
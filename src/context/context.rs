@@ -3,16 +3,26 @@
 //! This module provides the `Context` struct which wraps request data,
 //! headers, parameters, and provides access to application state.
 
-use axum::http::HeaderMap;
+use axum::http::{HeaderMap, Method, Uri};
 use http::Extensions;
+use std::net::SocketAddr;
+use std::str::FromStr;
 use std::sync::Arc;
 
-use crate::server::params::{Path, Query};
+use crate::api::filter::ListQuery;
+use crate::api::pagination::Pagination;
+use crate::middleware::Session;
+use crate::server::cookies::Cookies;
+use crate::server::params::{ParamError, Path, Query};
+use crate::server::upload::Uploads;
+
+static NO_UPLOADS: Uploads = Uploads::new(Vec::new());
 
 /// Request context passed to API handlers.
 ///
-/// Contains the deserialized request body, HTTP headers, path parameters,
-/// query parameters, and provides access to application state through extensions.
+/// Contains the deserialized request body, HTTP headers, request method and URI,
+/// path parameters, query parameters, and provides access to application state
+/// through extensions.
 ///
 /// Headers are wrapped in `Arc` for zero-copy sharing across async tasks.
 ///
@@ -100,6 +110,18 @@ pub struct Context<Req = ()> {
     /// HTTP request headers
     pub headers: Arc<HeaderMap>,
 
+    /// HTTP method of the request
+    pub method: Method,
+
+    /// Request URI, including the query string
+    pub uri: Uri,
+
+    /// Route template that matched the request (e.g., `/users/:id`)
+    pub matched_path: Option<String>,
+
+    /// Address of the remote peer, when the server was started with connection info
+    pub remote_addr: Option<SocketAddr>,
+
     /// Path parameters extracted from the URL (e.g., `:id` in `/users/:id`)
     pub path: Path,
 
@@ -160,6 +182,194 @@ impl<Req> Context<Req> {
         self.extensions.get::<S>().cloned()
     }
 
+    /// Get the typed query struct declared on the route.
+    ///
+    /// The struct is deserialized before the handler runs when the route declares it
+    /// with `Route::query_as::<T>()`.
+    ///
+    /// Returns `None` if the route did not declare a query struct of type `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Paging {
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// // Route::get("/users").query_as::<Paging>()
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let page = ctx.query_typed::<Paging>().and_then(|q| q.page).unwrap_or(1);
+    ///     Json(list_users(page).await)
+    /// }
+    /// ```
+    pub fn query_typed<T: 'static>(&self) -> Option<&T> {
+        self.query.typed::<T>()
+    }
+
+    /// Get the typed path struct declared on the route.
+    ///
+    /// The struct is deserialized before the handler runs when the route declares it
+    /// with `Route::path_as::<T>()`.
+    ///
+    /// Returns `None` if the route did not declare a path struct of type `T`.
+    pub fn path_typed<T: 'static>(&self) -> Option<&T> {
+        self.path.typed::<T>()
+    }
+
+    /// Get the files received in a `multipart/form-data` body.
+    ///
+    /// Empty unless the route uses `BodyEncoding::Multipart`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let Some(avatar) = ctx.uploads().get("avatar") else {
+    ///         return Err(Error::bad_request("missing_avatar", "Avatar file is required"));
+    ///     };
+    ///     store_avatar(avatar.file_name(), avatar.bytes()).await?;
+    ///     Ok(Response::no_content())
+    /// }
+    /// ```
+    pub fn uploads(&self) -> &Uploads {
+        self.extensions.get::<Uploads>().unwrap_or(&NO_UPLOADS)
+    }
+
+    /// Get the page requested by the client on a paginated route.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the route was not declared with `Route::paginate()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Route::get("/users").paginate(Paginate::offset())
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let page = ctx.pagination();
+    ///     let (users, total) = db::list_users(page.offset(), page.limit()).await?;
+    ///     Ok(page.page(users).total(total))
+    /// }
+    /// ```
+    pub fn pagination(&self) -> &Pagination {
+        self.extensions
+            .get::<Pagination>()
+            .expect("Pagination not found. Did you forget to call .paginate() on the route?")
+    }
+
+    /// Get the filters and sort requested by the client.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the route was not declared with `Route::filter()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // Route::get("/orders").filter(FilterSpec::new().field("status", [FilterOp::Eq]))
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let status = ctx.list_query().filters_on("status").next().map(Filter::value);
+    ///     Ok(Json(db::list_orders(status).await?))
+    /// }
+    /// ```
+    pub fn list_query(&self) -> &ListQuery {
+        self.extensions
+            .get::<ListQuery>()
+            .expect("List query not found. Did you forget to call .filter() on the route?")
+    }
+
+    /// Get the cookies sent with the request.
+    ///
+    /// The `Cookie` headers are parsed on each call. With the `secure-cookies` feature,
+    /// signed and private cookies use the key configured on the server builder.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let theme = ctx.cookies().value("theme").unwrap_or("light").to_string();
+    ///     Reply::ok(Settings { theme })
+    ///         .set_cookie(Cookie::build(("visited", "1")).path("/").http_only(true))
+    /// }
+    /// ```
+    pub fn cookies(&self) -> Cookies {
+        let cookies = Cookies::from_headers(&self.headers);
+        #[cfg(feature = "secure-cookies")]
+        let cookies = match self.extensions.get::<crate::server::cookies::CookieKey>() {
+            Some(key) => cookies.with_key(key.0.clone()),
+            None => cookies,
+        };
+        cookies
+    }
+
+    /// Get the value of a request header.
+    ///
+    /// Returns `None` if the header is missing or is not valid UTF-8. Headers declared
+    /// with `Route::header(..).required()` are always present in the handler.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// Parse a request header as any type that implements `FromStr`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// // route.header("X-Tenant-Id").required().schema::<u64>();
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let tenant: u64 = ctx.parse_header("X-Tenant-Id")?;
+    ///     Ok(Json(db::documents(tenant).await?))
+    /// }
+    /// ```
+    pub fn parse_header<T: FromStr>(&self, name: &str) -> Result<T, ParamError> {
+        parse_param(name, self.header(name))
+    }
+
+    /// Get the value of a request cookie.
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        Cookies::from_headers(&self.headers)
+            .value(name)
+            .map(str::to_string)
+    }
+
+    /// Parse a request cookie as any type that implements `FromStr`.
+    pub fn parse_cookie<T: FromStr>(&self, name: &str) -> Result<T, ParamError> {
+        parse_param(name, self.cookie(name).as_deref())
+    }
+
+    /// Get the session of the request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the endpoint is not wrapped by the [`Sessions`](crate::middleware::Sessions)
+    /// middleware. Add it with `.layer()` after registering the endpoints.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+    ///     let user = authenticate(&ctx.req).await?;
+    ///     let session = ctx.session();
+    ///     session.rotate();
+    ///     session.insert("user_id", user.id)?;
+    ///     Ok(Response::ok(user))
+    /// }
+    /// ```
+    pub fn session(&self) -> Session {
+        self.try_session().expect(
+            "Session not found. Did you forget to add the Sessions middleware with .layer()?",
+        )
+    }
+
+    /// Try to get the session of the request.
+    ///
+    /// Returns `None` if the endpoint is not wrapped by the
+    /// [`Sessions`](crate::middleware::Sessions) middleware.
+    pub fn try_session(&self) -> Option<Session> {
+        self.extensions.get::<Session>().cloned()
+    }
+
     /// Consume the context and return the request body.
     ///
     /// This allows you to move the request body out of the context,
@@ -203,6 +413,16 @@ impl<Req> Context<Req> {
     }
 }
 
+/// Parses a header or cookie value, reporting missing and invalid values as [`ParamError`].
+fn parse_param<T: FromStr>(name: &str, value: Option<&str>) -> Result<T, ParamError> {
+    let value = value.ok_or_else(|| ParamError::Missing(name.to_string()))?;
+    value.parse().map_err(|_| ParamError::InvalidType {
+        key: name.to_string(),
+        value: value.to_string(),
+        expected: std::any::type_name::<T>(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +442,10 @@ mod tests {
         let ctx = Context {
             req: (),
             headers: Arc::new(HeaderMap::new()),
+            method: Method::GET,
+            uri: Uri::default(),
+            matched_path: None,
+            remote_addr: None,
             path: Path::empty(),
             query: Query::empty(),
             extensions,
@@ -240,6 +464,10 @@ mod tests {
         let ctx = Context {
             req: (),
             headers: Arc::new(HeaderMap::new()),
+            method: Method::GET,
+            uri: Uri::default(),
+            matched_path: None,
+            remote_addr: None,
             path: Path::empty(),
             query: Query::empty(),
             extensions,
@@ -255,6 +483,10 @@ mod tests {
         let ctx = Context {
             req: (),
             headers: Arc::new(HeaderMap::new()),
+            method: Method::GET,
+            uri: Uri::default(),
+            matched_path: None,
+            remote_addr: None,
             path: Path::empty(),
             query: Query::empty(),
             extensions: Extensions::new(),
@@ -276,7 +276,12 @@ pub mod server;
 
 /// Helper macro for working with HandlerResult and validation.
 ///
-/// This macro simplifies the pattern of validating requests and returning HandlerResult.
+/// Runs the body in an async block, so it can `.await` and use `?` on any error
+/// convertible into [`Error`](crate::api::response::Error). The body evaluates to a
+/// `Result<T, Error>` that becomes the handler's `HandlerResult<T>`.
+///
+/// `HandlerResult` cannot implement the unstable `Try` trait, so `?` is not
+/// available directly in a handler returning it; wrap the body in `handle!` instead.
 ///
 /// # Example
 ///
@@ -293,13 +298,13 @@ pub mod server;
 ///         handle! {
 ///             ctx.req.validate()?;
 ///
-///             let user = UserResponse {
-///                 id: 1,
+///             let id = self.db.insert_user(&ctx.req).await?;
+///
+///             Ok(UserResponse {
+///                 id,
 ///                 name: ctx.req.name,
 ///                 email: ctx.req.email,
-///             };
-///
-///             user
+///             })
 ///         }
 ///     }
 /// }
@@ -308,14 +313,69 @@ pub mod server;
 macro_rules! handle {
     ($($body:tt)*) => {
         {
-            let result: Result<_, $crate::api::response::Error> = (|| {
+            let result: Result<_, $crate::api::response::Error> = async {
                 $($body)*
-            })();
+            }
+            .await;
             $crate::api::response::HandlerResult::from_result(result)
         }
     };
 }
 
+/// Implements `IntoResponse` and `OperationOutput` for an [`ApiError`](crate::api::response::ApiError) type.
+///
+/// Lets handlers return the type directly, for example as
+/// `type Response = Result<Json<User>, ShopError>;`. The response is rendered with
+/// `ApiError::into_api_response()` and documented under `ApiError::statuses()`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use uncovr::impl_api_error;
+///
+/// impl ApiError for ShopError {
+///     // ...
+/// }
+///
+/// impl_api_error!(ShopError);
+/// ```
+#[macro_export]
+macro_rules! impl_api_error {
+    ($ty:ty) => {
+        impl $crate::response::IntoResponse for $ty {
+            fn into_response(self) -> $crate::response::Response {
+                $crate::api::response::ApiError::into_api_response(self)
+            }
+        }
+
+        impl $crate::__private::aide::OperationOutput for $ty {
+            type Inner = Self;
+
+            fn operation_response(
+                ctx: &mut $crate::__private::aide::r#gen::GenContext,
+                operation: &mut $crate::__private::aide::openapi::Operation,
+            ) -> Option<$crate::__private::aide::openapi::Response> {
+                <$crate::extract::Json<Self> as $crate::__private::aide::OperationOutput>::operation_response(
+                    ctx, operation,
+                )
+            }
+
+            fn inferred_responses(
+                ctx: &mut $crate::__private::aide::r#gen::GenContext,
+                _operation: &mut $crate::__private::aide::openapi::Operation,
+            ) -> Vec<(Option<u16>, $crate::__private::aide::openapi::Response)> {
+                <Self as $crate::api::response::ApiError>::openapi_responses(ctx)
+            }
+        }
+    };
+}
+
+/// Items used by exported macros; not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use aide;
+}
+
 /// Testing utilities for integration tests
 #[cfg(feature = "testing")]
 pub mod testing;
@@ -377,6 +437,7 @@ pub mod tower {
 pub use async_trait;
 pub use schemars;
 pub use serde;
+#[cfg(feature = "validation")]
 pub use validator;
 
 #[cfg(test)]
@@ -388,6 +449,10 @@ mod tests {
         let ctx = Context::<()> {
             req: (),
             headers: Default::default(),
+            method: Default::default(),
+            uri: Default::default(),
+            matched_path: None,
+            remote_addr: None,
             path: crate::server::Path::new(Default::default()),
             query: crate::server::Query::new(Default::default()),
             extensions: Default::default(),
@@ -25,10 +25,15 @@
 
 mod auth;
 mod cors;
+mod negotiate;
 mod rate_limit;
 mod request_id;
+mod session;
 
 pub use auth::BearerAuth;
 pub use cors::Cors;
+pub use negotiate::{ContentNegotiation, ResponseFormat};
+pub(crate) use negotiate::{NotAcceptable, Unnegotiated, is_json_media_type, not_acceptable_error};
 pub use rate_limit::RateLimit;
-pub use request_id::RequestId;
+pub use request_id::{RequestId, RequestIdValue};
+pub use session::{FileStore, MemoryStore, Session, SessionRecord, SessionStore, Sessions};
@@ -0,0 +1,410 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use tower::{Layer, Service};
+
+use crate::api::response::{Error, into_problem_details};
+
+/// Serialization format for negotiated responses.
+///
+/// JSON is always available. Other formats are enabled with the `msgpack`, `cbor`
+/// and `yaml` cargo features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// `application/json`
+    Json,
+    /// `application/msgpack`
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    /// `application/cbor`
+    #[cfg(feature = "cbor")]
+    Cbor,
+    /// `application/yaml`
+    #[cfg(feature = "yaml")]
+    Yaml,
+}
+
+impl ResponseFormat {
+    /// Formats enabled in this build, in order of server preference.
+    pub const ALL: &'static [ResponseFormat] = &[
+        Self::Json,
+        #[cfg(feature = "msgpack")]
+        Self::MsgPack,
+        #[cfg(feature = "cbor")]
+        Self::Cbor,
+        #[cfg(feature = "yaml")]
+        Self::Yaml,
+    ];
+
+    /// Returns the media type sent in the `Content-Type` header.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+            #[cfg(feature = "yaml")]
+            Self::Yaml => "application/yaml",
+        }
+    }
+
+    /// Returns whether a media type (without parameters) names this format.
+    ///
+    /// Common aliases such as `application/x-msgpack` and `text/yaml` are accepted.
+    fn is_named(&self, media_type: &str) -> bool {
+        match self {
+            Self::Json => media_type == "application/json",
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => matches!(
+                media_type,
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+            ),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => media_type == "application/cbor",
+            #[cfg(feature = "yaml")]
+            Self::Yaml => matches!(
+                media_type,
+                "application/yaml" | "application/x-yaml" | "text/yaml"
+            ),
+        }
+    }
+
+    /// Picks the preferred format for an `Accept` header value.
+    ///
+    /// Media ranges are weighted by their `q` parameter, with the most specific
+    /// matching range deciding each format's weight. Ties go to the earlier format
+    /// in [`ResponseFormat::ALL`]. Returns `None` when no format is acceptable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::middleware::ResponseFormat;
+    ///
+    /// let format = ResponseFormat::negotiate("text/html, application/json;q=0.9");
+    /// assert_eq!(format, Some(ResponseFormat::Json));
+    ///
+    /// assert_eq!(ResponseFormat::negotiate("text/html"), None);
+    /// ```
+    pub fn negotiate(accept: &str) -> Option<Self> {
+        let ranges: Vec<(String, f32)> = accept.split(',').filter_map(parse_media_range).collect();
+
+        let mut best: Option<(Self, f32)> = None;
+        for format in Self::ALL {
+            let quality = ranges
+                .iter()
+                .filter_map(|(range, q)| format.specificity(range).map(|s| (s, *q)))
+                .max_by_key(|(specificity, _)| *specificity)
+                .map(|(_, q)| q)
+                .unwrap_or(0.0);
+
+            if quality > 0.0 && best.is_none_or(|(_, best_q)| quality > best_q) {
+                best = Some((*format, quality));
+            }
+        }
+
+        best.map(|(format, _)| format)
+    }
+
+    /// Returns how specifically a media range matches this format, if at all.
+    fn specificity(&self, range: &str) -> Option<u8> {
+        if range == "*/*" {
+            return Some(0);
+        }
+        if let Some(kind) = range.strip_suffix("/*") {
+            let (format_kind, _) = self.media_type().split_once('/')?;
+            return (kind == format_kind || (kind == "text" && self.is_named("text/yaml")))
+                .then_some(1);
+        }
+        self.is_named(range).then_some(2)
+    }
+
+    /// Re-encodes a JSON document in this format.
+    fn encode(&self, value: &serde_json::Value) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|err| err.to_string()),
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|err| err.to_string()),
+            #[cfg(feature = "cbor")]
+            Self::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(value, &mut buf).map_err(|err| err.to_string())?;
+                Ok(buf)
+            }
+            #[cfg(feature = "yaml")]
+            Self::Yaml => serde_yaml::to_string(value)
+                .map(String::into_bytes)
+                .map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// Parses one media range of an `Accept` header into its lowercase type and quality.
+fn parse_media_range(range: &str) -> Option<(String, f32)> {
+    let mut parts = range.split(';');
+    let media_type = parts.next()?.trim().to_ascii_lowercase();
+    if media_type.is_empty() {
+        return None;
+    }
+
+    let quality = parts
+        .filter_map(|param| param.trim().strip_prefix("q="))
+        .find_map(|q| q.trim().parse::<f32>().ok())
+        .unwrap_or(1.0);
+
+    Some((media_type, quality))
+}
+
+/// Returns whether a media type is JSON, including `+json` suffixes.
+pub(crate) fn is_json_media_type(media_type: &str) -> bool {
+    let essence = media_type.split(';').next().unwrap_or_default().trim();
+    essence.eq_ignore_ascii_case("application/json")
+        || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+/// Returns whether a `Content-Type` value is JSON, including `+json` suffixes.
+fn is_json(content_type: &HeaderValue) -> bool {
+    content_type.to_str().is_ok_and(is_json_media_type)
+}
+
+/// Returns whether a `Content-Type` value is plain `application/json`.
+///
+/// Structured `+json` types such as `application/problem+json` have no equivalent in
+/// the other formats, so they are sent as JSON whatever the client prefers.
+fn is_plain_json(content_type: &HeaderValue) -> bool {
+    content_type.to_str().is_ok_and(|content_type| {
+        let essence = content_type.split(';').next().unwrap_or_default().trim();
+        essence.eq_ignore_ascii_case("application/json")
+    })
+}
+
+/// Request extension marking a request whose `Accept` header allows no enabled format.
+///
+/// Endpoints that document JSON responses reject such requests with 406 before their
+/// handler runs.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct NotAcceptable;
+
+/// Response extension exempting a response from content negotiation.
+///
+/// Set on the documentation routes, whose UI and specification are served as-is.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Unnegotiated;
+
+/// Content negotiation middleware for JSON responses.
+///
+/// Inspects the request's `Accept` header and re-encodes JSON responses, including
+/// [`Error`](crate::api::Error) bodies, in the preferred [`ResponseFormat`]. Requests
+/// without an `Accept` header receive JSON. When the header accepts none of the
+/// enabled formats, JSON responses are replaced with 406 Not Acceptable; registered
+/// endpoints that document JSON responses reject the request before their handler runs.
+///
+/// Responses that are not JSON (HTML, plain text, files, event streams) and the
+/// documentation routes are passed through unchanged. Problem details keep their
+/// `application/problem+json` type.
+///
+/// Prefer [`ServerBuilder::with_content_negotiation()`](crate::server::ServerBuilder::with_content_negotiation),
+/// which also lists the produced media types in the OpenAPI specification.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use uncovr::server::Server;
+/// use uncovr::middleware::ContentNegotiation;
+///
+/// let server = Server::new()
+///     .layer(ContentNegotiation::new())
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ContentNegotiation {
+    problem_details: bool,
+}
+
+impl ContentNegotiation {
+    /// Creates a content negotiation middleware for all enabled formats.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renders 406 Not Acceptable responses as problem details.
+    pub(crate) fn problem_details(mut self, enable: bool) -> Self {
+        self.problem_details = enable;
+        self
+    }
+}
+
+impl<S> Layer<S> for ContentNegotiation {
+    type Service = ContentNegotiationService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ContentNegotiationService {
+            inner,
+            problem_details: self.problem_details,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ContentNegotiationService<S> {
+    inner: S,
+    problem_details: bool,
+}
+
+impl<S> Service<Request<Body>> for ContentNegotiationService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        // Missing or unreadable Accept headers fall back to JSON
+        let format = match req
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(accept) => ResponseFormat::negotiate(accept),
+            None => Some(ResponseFormat::Json),
+        };
+
+        // Endpoints check the marker so their side effects are not discarded
+        if format.is_none() {
+            req.extensions_mut().insert(NotAcceptable);
+        }
+
+        let problem_details = self.problem_details;
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let response = inner.call(req).await?;
+
+            let negotiable = response.extensions().get::<Unnegotiated>().is_none()
+                && response
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .is_some_and(is_json);
+            if !negotiable {
+                return Ok(response);
+            }
+
+            let Some(format) = format else {
+                let response = if problem_details {
+                    into_problem_details(not_acceptable())
+                } else {
+                    not_acceptable()
+                };
+                return Ok(vary_accept(response));
+            };
+
+            let transcodable = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .is_some_and(is_plain_json);
+            let response = if format != ResponseFormat::Json && transcodable {
+                transcode(response, format).await
+            } else {
+                response
+            };
+            Ok(vary_accept(response))
+        })
+    }
+}
+
+/// Marks a response as depending on the request's `Accept` header.
+fn vary_accept(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+/// Re-encodes a JSON response body, leaving the response untouched if it is not valid JSON.
+async fn transcode(response: Response, format: ResponseFormat) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+
+    let encoded = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .map_err(|err| err.to_string())
+        .and_then(|value| format.encode(&value));
+
+    match encoded {
+        Ok(encoded) => {
+            parts.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(format.media_type()),
+            );
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(encoded))
+        }
+        Err(_) => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
+
+fn not_acceptable() -> Response {
+    not_acceptable_error().into_response()
+}
+
+/// Builds the 406 Not Acceptable error listing the enabled formats.
+pub(crate) fn not_acceptable_error() -> Error {
+    let supported: Vec<&str> = ResponseFormat::ALL
+        .iter()
+        .map(ResponseFormat::media_type)
+        .collect();
+
+    Error::not_acceptable(
+        "not_acceptable",
+        format!(
+            "None of the requested media types are supported. Supported: {}",
+            supported.join(", ")
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(ResponseFormat::negotiate("*/*"), Some(ResponseFormat::Json));
+        assert_eq!(
+            ResponseFormat::negotiate("application/*"),
+            Some(ResponseFormat::Json)
+        );
+        assert_eq!(
+            ResponseFormat::negotiate("text/html;q=0.9, application/json;q=0.1"),
+            Some(ResponseFormat::Json)
+        );
+        assert_eq!(ResponseFormat::negotiate("application/json;q=0"), None);
+        assert_eq!(ResponseFormat::negotiate("text/html"), None);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_negotiate_yaml() {
+        assert_eq!(
+            ResponseFormat::negotiate("application/json;q=0.5, text/yaml"),
+            Some(ResponseFormat::Yaml)
+        );
+        assert_eq!(
+            ResponseFormat::negotiate("text/*"),
+            Some(ResponseFormat::Yaml)
+        );
+    }
+}
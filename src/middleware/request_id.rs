@@ -7,6 +7,12 @@ use axum::{
 use tower::{Layer, Service};
 use uuid::Uuid;
 
+/// Identifier assigned to a request by the [`RequestId`] middleware.
+///
+/// Stored in both the request and the response extensions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestIdValue(pub String);
+
 /// Request ID middleware for tracing requests across uncovr applications.
 ///
 /// Generates unique identifiers for each request and adds them to both request and response headers.
@@ -100,6 +106,8 @@ where
             HeaderName::from_bytes(header_name.as_bytes()).unwrap(),
             request_id.clone(),
         );
+        let id = RequestIdValue(request_id.to_str().unwrap_or_default().to_string());
+        req.extensions_mut().insert(id.clone());
 
         let mut inner = self.inner.clone();
         let header_name_for_response = header_name.clone();
@@ -112,6 +120,7 @@ where
                 HeaderName::from_bytes(header_name_for_response.as_bytes()).unwrap(),
                 request_id,
             );
+            response.extensions_mut().insert(id);
 
             Ok(response)
         })
@@ -0,0 +1,687 @@
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tower::{Layer, Service};
+use uuid::Uuid;
+
+use crate::api::response::Error;
+use crate::server::cookies::{Cookie, Cookies, SameSite};
+
+/// Session data as kept by a [`SessionStore`].
+///
+/// Timestamps are seconds since the Unix epoch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecord {
+    /// Values stored in the session
+    pub data: Map<String, Value>,
+    /// When the session was created
+    pub created_at: u64,
+    /// When the session was last used
+    pub last_active: u64,
+    /// When the session expires unless it is used again
+    pub expires_at: u64,
+}
+
+impl SessionRecord {
+    /// Check if the session has expired at the given time
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Storage backend for server-side sessions.
+///
+/// Implementations are keyed by session ID. The [`Sessions`] middleware checks
+/// expiry itself; stores may use [`SessionRecord::expires_at`] to discard old
+/// sessions.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use uncovr::middleware::{SessionRecord, SessionStore};
+///
+/// struct RedisStore { client: redis::Client }
+///
+/// #[async_trait::async_trait]
+/// impl SessionStore for RedisStore {
+///     async fn load(&self, id: &str) -> std::io::Result<Option<SessionRecord>> { ... }
+///     async fn save(&self, id: &str, record: &SessionRecord) -> std::io::Result<()> { ... }
+///     async fn delete(&self, id: &str) -> std::io::Result<()> { ... }
+/// }
+/// ```
+#[async_trait::async_trait]
+pub trait SessionStore: Send + Sync + 'static {
+    /// Load a session, returning `None` if it does not exist
+    async fn load(&self, id: &str) -> io::Result<Option<SessionRecord>>;
+
+    /// Create or replace a session
+    async fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()>;
+
+    /// Delete a session; deleting a missing session is not an error
+    async fn delete(&self, id: &str) -> io::Result<()>;
+}
+
+/// In-memory session store.
+///
+/// Sessions are lost when the process exits and are not shared between instances.
+/// Expired sessions are discarded when other sessions are saved.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    sessions: Arc<Mutex<HashMap<String, SessionRecord>>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty in-memory store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored sessions, including expired ones not yet discarded
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// Check if no sessions are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for MemoryStore {
+    async fn load(&self, id: &str) -> io::Result<Option<SessionRecord>> {
+        Ok(self.sessions.lock().unwrap().get(id).cloned())
+    }
+
+    async fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()> {
+        let now = unix_now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, record| !record.is_expired(now));
+        sessions.insert(id.to_string(), record.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> io::Result<()> {
+        self.sessions.lock().unwrap().remove(id);
+        Ok(())
+    }
+}
+
+/// File-backed session store.
+///
+/// Each session is kept as a JSON file named after its ID in a directory, which is
+/// created on first use. Sessions survive restarts and can be shared by instances
+/// on the same machine. Call [`FileStore::purge_expired()`] periodically to remove
+/// abandoned sessions.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Creates a store that keeps sessions in the given directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Delete expired session files, returning how many were removed
+    pub async fn purge_expired(&self) -> io::Result<usize> {
+        let now = unix_now();
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let mut removed = 0;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let expired = match tokio::fs::read(&path).await {
+                Ok(bytes) => serde_json::from_slice::<SessionRecord>(&bytes)
+                    .is_ok_and(|record| record.is_expired(now)),
+                Err(_) => false,
+            };
+            if expired && remove_file(&path).await.is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for FileStore {
+    async fn load(&self, id: &str) -> io::Result<Option<SessionRecord>> {
+        let bytes = match tokio::fs::read(self.path(id)).await {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    async fn save(&self, id: &str, record: &SessionRecord) -> io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let bytes = serde_json::to_vec(record)?;
+
+        // Write to a temporary file first so readers never see a partial session
+        let path = self.path(id);
+        let temp = self
+            .dir
+            .join(format!("{}.{}.tmp", id, Uuid::new_v4().simple()));
+        tokio::fs::write(&temp, bytes).await?;
+        tokio::fs::rename(&temp, &path).await
+    }
+
+    async fn delete(&self, id: &str) -> io::Result<()> {
+        remove_file(&self.path(id)).await
+    }
+}
+
+async fn remove_file(path: &std::path::Path) -> io::Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// What the handler did with the session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    None,
+    Modified,
+    Rotated,
+    Destroyed,
+}
+
+#[derive(Debug)]
+struct SessionState {
+    id: Option<String>,
+    record: SessionRecord,
+    change: Change,
+}
+
+/// The current request's session, read with `ctx.session()`.
+///
+/// Values are stored as JSON under string keys. Changes are saved by the
+/// [`Sessions`] middleware after the handler returns, and a session cookie is only
+/// sent once something has been stored.
+///
+/// Call [`rotate()`](Session::rotate) after a successful login so that a session
+/// ID set before authentication cannot be reused, and
+/// [`destroy()`](Session::destroy) on logout.
+#[derive(Debug, Clone)]
+pub struct Session {
+    state: Arc<Mutex<SessionState>>,
+}
+
+impl Session {
+    fn new(id: Option<String>, record: SessionRecord) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SessionState {
+                id,
+                record,
+                change: Change::None,
+            })),
+        }
+    }
+
+    /// Session ID, or `None` for a session that has not been saved yet
+    pub fn id(&self) -> Option<String> {
+        self.state.lock().unwrap().id.clone()
+    }
+
+    /// Get a value, returning `None` if it is missing or has a different type
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let state = self.state.lock().unwrap();
+        let value = state.record.data.get(key)?;
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// Store a value, replacing any previous value under the key
+    pub fn insert<T: Serialize>(&self, key: &str, value: T) -> Result<(), Error> {
+        let value = serde_json::to_value(value).map_err(|err| {
+            tracing::error!("Failed to serialize session value `{}`: {}", key, err);
+            Error::internal("session_error", "Internal server error")
+        })?;
+        let mut state = self.state.lock().unwrap();
+        state.record.data.insert(key.to_string(), value);
+        state.mark(Change::Modified);
+        Ok(())
+    }
+
+    /// Remove a value, returning it if it had the requested type
+    pub fn remove<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let value = state.record.data.remove(key)?;
+        state.mark(Change::Modified);
+        serde_json::from_value(value).ok()
+    }
+
+    /// Check if a value is stored under the key
+    pub fn contains(&self, key: &str) -> bool {
+        self.state.lock().unwrap().record.data.contains_key(key)
+    }
+
+    /// Check if the session holds no values
+    pub fn is_empty(&self) -> bool {
+        self.state.lock().unwrap().record.data.is_empty()
+    }
+
+    /// Remove all values, keeping the session
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.record.data.clear();
+        state.mark(Change::Modified);
+    }
+
+    /// Move the session to a new ID, keeping its values.
+    ///
+    /// The old ID stops working when the response is sent.
+    pub fn rotate(&self) {
+        self.state.lock().unwrap().mark(Change::Rotated);
+    }
+
+    /// Delete the session and remove the session cookie from the client
+    pub fn destroy(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.record.data.clear();
+        state.change = Change::Destroyed;
+    }
+}
+
+impl SessionState {
+    fn mark(&mut self, change: Change) {
+        // A destroyed session stays destroyed, and a rotation is not undone by edits
+        if self.change == Change::Destroyed || self.change == Change::Rotated {
+            return;
+        }
+        self.change = change;
+    }
+}
+
+/// Server-side session middleware.
+///
+/// Keeps session data in a [`SessionStore`] and the session ID in an `HttpOnly`
+/// cookie. Handlers read and write the session with `ctx.session()`.
+///
+/// Sessions expire after [`idle_timeout()`](Sessions::idle_timeout) without
+/// requests (default 1 hour) and [`absolute_timeout()`](Sessions::absolute_timeout)
+/// after they were created (default 24 hours). Expired sessions start over empty.
+///
+/// Like other middleware added with [`ServerBuilder::layer()`](crate::server::ServerBuilder::layer),
+/// it only wraps endpoints registered before it. Document protected endpoints with
+/// `Meta::security(SecurityScheme::Session { cookie: "session" })`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use uncovr::server::Server;
+/// use uncovr::middleware::{FileStore, Sessions};
+/// use std::time::Duration;
+///
+/// let server = Server::new()
+///     .register(Login)
+///     .register(Dashboard)
+///     .layer(
+///         Sessions::new(FileStore::new("./sessions"))
+///             .idle_timeout(Duration::from_secs(30 * 60)),
+///     )
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct Sessions {
+    store: Arc<dyn SessionStore>,
+    config: Arc<SessionConfig>,
+}
+
+#[derive(Clone)]
+struct SessionConfig {
+    cookie_name: &'static str,
+    path: String,
+    domain: Option<String>,
+    secure: bool,
+    same_site: SameSite,
+    idle_timeout: Duration,
+    absolute_timeout: Duration,
+}
+
+impl Sessions {
+    /// Creates a session middleware backed by a store.
+    pub fn new(store: impl SessionStore) -> Self {
+        Self {
+            store: Arc::new(store),
+            config: Arc::new(SessionConfig {
+                cookie_name: "session",
+                path: "/".to_string(),
+                domain: None,
+                secure: true,
+                same_site: SameSite::Lax,
+                idle_timeout: Duration::from_secs(60 * 60),
+                absolute_timeout: Duration::from_secs(24 * 60 * 60),
+            }),
+        }
+    }
+
+    /// Set the session cookie name (default: `session`)
+    pub fn cookie_name(mut self, name: &'static str) -> Self {
+        self.config_mut().cookie_name = name;
+        self
+    }
+
+    /// Set the session cookie path (default: `/`)
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.config_mut().path = path.into();
+        self
+    }
+
+    /// Set the session cookie domain (default: the request host only)
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.config_mut().domain = Some(domain.into());
+        self
+    }
+
+    /// Send the session cookie over HTTPS only (default: true)
+    ///
+    /// Browsers accept secure cookies from `http://localhost`.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.config_mut().secure = secure;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the session cookie (default: `Lax`)
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.config_mut().same_site = same_site;
+        self
+    }
+
+    /// Expire sessions that receive no requests for this long (default: 1 hour)
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config_mut().idle_timeout = timeout;
+        self
+    }
+
+    /// Expire sessions this long after they were created (default: 24 hours)
+    pub fn absolute_timeout(mut self, timeout: Duration) -> Self {
+        self.config_mut().absolute_timeout = timeout;
+        self
+    }
+
+    fn config_mut(&mut self) -> &mut SessionConfig {
+        Arc::make_mut(&mut self.config)
+    }
+}
+
+impl SessionConfig {
+    fn cookie(&self, id: String) -> Cookie<'static> {
+        let mut cookie = Cookie::build((self.cookie_name, id))
+            .path(self.path.clone())
+            .http_only(true)
+            .secure(self.secure)
+            .same_site(self.same_site)
+            .max_age(cookie::time::Duration::seconds(
+                self.absolute_timeout.as_secs() as i64,
+            ))
+            .build();
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        cookie
+    }
+
+    fn expires_at(&self, record: &SessionRecord) -> u64 {
+        let idle = record.last_active + self.idle_timeout.as_secs();
+        let absolute = record.created_at + self.absolute_timeout.as_secs();
+        idle.min(absolute)
+    }
+}
+
+impl<S> Layer<S> for Sessions {
+    type Service = SessionService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SessionService {
+            inner,
+            store: self.store.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SessionService<S> {
+    inner: S,
+    store: Arc<dyn SessionStore>,
+    config: Arc<SessionConfig>,
+}
+
+impl<S> Service<Request<Body>> for SessionService<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = futures::future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<Body>) -> Self::Future {
+        // IDs are only accepted in the format we generate, which also keeps
+        // arbitrary cookie values out of store keys and file names
+        let cookie_id = Cookies::from_headers(req.headers())
+            .value(self.config.cookie_name)
+            .filter(|id| is_session_id(id))
+            .map(str::to_string);
+
+        let mut inner = self.inner.clone();
+        let store = self.store.clone();
+        let config = self.config.clone();
+
+        Box::pin(async move {
+            let now = unix_now();
+            let loaded = match &cookie_id {
+                Some(id) => match store.load(id).await {
+                    Ok(record) => record.filter(|record| !record.is_expired(now)),
+                    Err(err) => return Ok(store_error("load", err)),
+                },
+                None => None,
+            };
+
+            let found = loaded.is_some();
+            let session = match loaded {
+                Some(record) => Session::new(cookie_id.clone(), record),
+                None => Session::new(None, SessionRecord::default()),
+            };
+            req.extensions_mut().insert(session.clone());
+
+            let mut response = inner.call(req).await?;
+
+            let (id, record, change) = {
+                let mut state = session.state.lock().unwrap();
+                let record = std::mem::take(&mut state.record);
+                (state.id.take(), record, state.change)
+            };
+
+            let result = commit(&*store, &config, id, record, change, now).await;
+            let cookie = match result {
+                Ok(Commit::SetCookie(id)) => Some(config.cookie(id)),
+                Ok(Commit::Keep) => None,
+                Ok(Commit::Removed) => {
+                    let mut cookie = config.cookie(String::new());
+                    cookie.make_removal();
+                    Some(cookie)
+                }
+                Err(err) => return Ok(store_error("save", err)),
+            };
+
+            // A cookie for a session that no longer exists is cleared as well
+            let cookie = cookie.or_else(|| {
+                let stale = cookie_id.is_some() && !found;
+                stale.then(|| {
+                    let mut cookie = config.cookie(String::new());
+                    cookie.make_removal();
+                    cookie
+                })
+            });
+
+            if let Some(cookie) = cookie {
+                if let Ok(value) = HeaderValue::try_from(cookie.encoded().to_string()) {
+                    response.headers_mut().append(header::SET_COOKIE, value);
+                }
+            }
+            Ok(response)
+        })
+    }
+}
+
+enum Commit {
+    SetCookie(String),
+    Keep,
+    Removed,
+}
+
+/// Saves or deletes the session after the handler ran.
+async fn commit(
+    store: &dyn SessionStore,
+    config: &SessionConfig,
+    id: Option<String>,
+    mut record: SessionRecord,
+    change: Change,
+    now: u64,
+) -> io::Result<Commit> {
+    match (change, id) {
+        (Change::Destroyed, Some(id)) => {
+            store.delete(&id).await?;
+            Ok(Commit::Removed)
+        }
+        (Change::Destroyed, None) => Ok(Commit::Keep),
+        // New sessions are only created once they hold something
+        (_, None) if record.data.is_empty() => Ok(Commit::Keep),
+        (Change::None, Some(id)) => {
+            // Refresh the idle timeout, at most once a minute for unchanged sessions
+            if now.saturating_sub(record.last_active) >= 60 {
+                record.last_active = now;
+                record.expires_at = config.expires_at(&record);
+                store.save(&id, &record).await?;
+            }
+            Ok(Commit::Keep)
+        }
+        (Change::Modified, Some(id)) => {
+            record.last_active = now;
+            record.expires_at = config.expires_at(&record);
+            store.save(&id, &record).await?;
+            Ok(Commit::Keep)
+        }
+        (_, old_id) => {
+            // New sessions and rotations get a fresh ID
+            let id = new_session_id();
+            if old_id.is_none() {
+                record.created_at = now;
+            }
+            record.last_active = now;
+            record.expires_at = config.expires_at(&record);
+            store.save(&id, &record).await?;
+            if let Some(old_id) = old_id {
+                store.delete(&old_id).await?;
+            }
+            Ok(Commit::SetCookie(id))
+        }
+    }
+}
+
+fn store_error(action: &str, err: io::Error) -> Response {
+    tracing::error!("Failed to {} session: {}", action, err);
+    Error::internal("session_error", "Internal server error").into_response()
+}
+
+/// Generates a session ID with 244 random bits.
+fn new_session_id() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+fn is_session_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(created_at: u64, last_active: u64) -> SessionRecord {
+        let mut data = Map::new();
+        data.insert("user".to_string(), Value::from(42));
+        SessionRecord {
+            data,
+            created_at,
+            last_active,
+            expires_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_session_expiry() {
+        let sessions = Sessions::new(MemoryStore::new())
+            .idle_timeout(Duration::from_secs(60))
+            .absolute_timeout(Duration::from_secs(3600));
+
+        assert_eq!(sessions.config.expires_at(&record(1000, 1000)), 1060);
+        assert_eq!(sessions.config.expires_at(&record(1000, 4590)), 4600);
+        assert!(record(0, 0).is_expired(unix_now()));
+    }
+
+    #[tokio::test]
+    async fn test_file_store() {
+        let dir = std::env::temp_dir().join(format!("uncovr-sessions-{}", Uuid::new_v4()));
+        let store = FileStore::new(&dir);
+        let id = new_session_id();
+        assert!(is_session_id(&id));
+        assert_eq!(store.load(&id).await.unwrap(), None);
+
+        let mut session = record(unix_now(), unix_now());
+        session.expires_at = unix_now() + 60;
+        store.save(&id, &session).await.unwrap();
+        assert_eq!(store.load(&id).await.unwrap(), Some(session));
+
+        store.save(&new_session_id(), &record(1, 1)).await.unwrap();
+        assert_eq!(store.purge_expired().await.unwrap(), 1);
+
+        store.delete(&id).await.unwrap();
+        store.delete(&id).await.unwrap();
+        assert_eq!(store.load(&id).await.unwrap(), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
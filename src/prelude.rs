@@ -4,11 +4,17 @@
 
 // Core Uncovr types
 pub use crate::api::api::Handler;
-pub use crate::api::response::{Error, HandlerResult, JsonResponse, Response};
+pub use crate::api::filter::{FilterOp, FilterSpec, ListQuery};
+pub use crate::api::pagination::{Page, Paginate, Pagination};
+pub use crate::api::response::{ApiError, Error, HandlerResult, JsonResponse, Reply, Response};
 pub use crate::config::{App, LogFormat, LogLevel, Logging};
 pub use crate::context::Context;
+pub use crate::server::cookies::{Cookie, Cookies};
 pub use crate::server::params::{Path, Query};
-pub use crate::server::{ApiKeyLocation, Endpoint, HttpMethod, Meta, Route, SecurityScheme};
+pub use crate::server::upload::{Upload, Uploads};
+pub use crate::server::{
+    ApiKeyLocation, Endpoint, HttpMethod, Layers, Meta, Route, SecurityScheme,
+};
 
 // Core traits
 pub use async_trait::async_trait;
@@ -20,4 +26,5 @@ pub use axum::http::{HeaderMap, StatusCode};
 pub use axum::response::IntoResponse;
 
 // Validation
+#[cfg(feature = "validation")]
 pub use validator::{Validate, ValidationError, ValidationErrors};
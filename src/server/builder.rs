@@ -1,57 +1,675 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
 use std::{convert::Infallible, net::SocketAddr};
 use tokio::net::TcpListener;
 
 use aide::OperationIo;
 use aide::axum::{
     ApiRouter,
-    routing::{delete_with, get_with, patch_with, post_with, put_with},
+    routing::{
+        delete_with, get_with, head_with, options_with, patch_with, post_with, put_with, trace_with,
+    },
 };
 use aide::openapi::{
-    ApiKeyLocation as OpenApiKeyLocation, Parameter, ParameterData, ParameterSchemaOrContent,
-    QueryStyle, ReferenceOr, SchemaObject, SecurityRequirement,
-    SecurityScheme as OpenApiSecurityScheme,
+    ApiKeyLocation as OpenApiKeyLocation, CookieStyle, HeaderStyle, MediaType, Operation,
+    Parameter, ParameterData, ParameterSchemaOrContent, PathStyle, QueryStyle, ReferenceOr,
+    SchemaObject, SecurityRequirement, SecurityScheme as OpenApiSecurityScheme,
 };
+use aide::transform::TransformOperation;
 use async_trait::async_trait;
-use axum::{Extension, body::Body};
+use axum::response::IntoResponse;
 use axum::{
-    extract::FromRequestParts,
-    http::{Request, Response, request::Parts},
+    Extension,
+    body::{Body, Bytes},
+};
+use axum::{
+    extract::{
+        ConnectInfo, DefaultBodyLimit, FromRequest, FromRequestParts, MatchedPath, Multipart,
+        OriginalUri, rejection::PathRejection,
+    },
+    http::{
+        HeaderMap, HeaderValue, Method, Request, Response, StatusCode, Uri,
+        header::{ALLOW, CONTENT_TYPE},
+        request::Parts,
+    },
 };
 use http::Extensions;
-use tower::Service;
+use schemars::JsonSchema;
+use schemars::schema::InstanceType;
+use serde::de::DeserializeOwned;
+use tower::{Service, ServiceExt};
 use tower_http::trace::{MakeSpan, OnResponse, TraceLayer};
 
 use crate::api::api::Handler;
+use crate::api::filter::FilterSpec;
+use crate::api::response::{Error, ErrorInfo, ProblemDetails, into_problem_details};
 use crate::config::App;
 use crate::context::Context;
+use crate::middleware::{
+    ContentNegotiation, NotAcceptable, ResponseFormat, Unnegotiated, is_json_media_type,
+    not_acceptable_error,
+};
 use crate::openapi::{OpenApiConfig, serve_docs, serve_scalar_ui};
-use crate::server::endpoint::{ApiKeyLocation, Endpoint as EndpointTrait, SecurityScheme};
+use crate::server::cookies::Cookies;
+use crate::server::endpoint::{
+    ApiKeyLocation, BodyEncoding, BodyPolicy, DeclaredError, Endpoint as EndpointTrait, HttpMethod,
+    Layers, ParamSchema, ParamStyle, ResponseCallback, ResponseHeader, Route, SecurityScheme,
+    TypedParams,
+};
 use crate::server::params::{Path, Query};
+use crate::server::upload::{Upload, Uploads};
 
-/// Custom extractor for HTTP Extensions.
+/// Custom extractor for the request head.
 ///
-/// This internal extractor allows uncovr to extract the entire Extensions map from incoming requests
-/// and pass it through to the [`Context`], enabling handlers to access application state and
-/// request-scoped data via `ctx.state::<T>()`.
+/// This internal extractor allows uncovr to extract the headers, method, URI, matched route,
+/// remote address and the entire Extensions map from incoming requests and pass them through
+/// to the [`Context`], enabling handlers to read request metadata and access application state
+/// and request-scoped data via `ctx.state::<T>()`.
 ///
 /// This is part of uncovr's abstraction layer that hides Axum implementation details from users.
 #[derive(OperationIo)]
 #[aide(input)]
-struct ExtractExtensions(Extensions);
+struct ExtractRequestParts {
+    headers: Arc<HeaderMap>,
+    method: Method,
+    uri: Uri,
+    /// URI before nested routers stripped their prefix, for links back to the request
+    original_uri: Uri,
+    matched_path: Option<String>,
+    remote_addr: Option<SocketAddr>,
+    extensions: Extensions,
+}
+
+impl ExtractRequestParts {
+    /// Builds the handler [`Context`] from the request head and the extracted parameters.
+    fn into_context<Req>(self, req: Req, path: Path, query: Query) -> Context<Req> {
+        Context {
+            req,
+            headers: self.headers,
+            method: self.method,
+            uri: self.uri,
+            matched_path: self.matched_path,
+            remote_addr: self.remote_addr,
+            path,
+            query,
+            extensions: self.extensions,
+        }
+    }
+}
 
 #[async_trait]
-impl<S> FromRequestParts<S> for ExtractExtensions
+impl<S> FromRequestParts<S> for ExtractRequestParts
 where
     S: Send + Sync,
 {
     type Rejection = Infallible;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        Ok(ExtractExtensions(parts.extensions.clone()))
+        Ok(ExtractRequestParts {
+            headers: Arc::new(parts.headers.clone()),
+            method: parts.method.clone(),
+            uri: parts.uri.clone(),
+            original_uri: parts
+                .extensions
+                .get::<OriginalUri>()
+                .map_or_else(|| parts.uri.clone(), |OriginalUri(uri)| uri.clone()),
+            matched_path: parts
+                .extensions
+                .get::<MatchedPath>()
+                .map(|matched| matched.as_str().to_string()),
+            remote_addr: parts
+                .extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| *addr),
+            extensions: parts.extensions.clone(),
+        })
+    }
+}
+
+/// Raw request extractor for the body, which is read according to the route's [`BodyConfig`].
+///
+/// The request body is documented by [`OperationDocs`] instead, since its media type
+/// depends on the route.
+struct RawRequest(Request<Body>);
+
+#[async_trait]
+impl<S> FromRequest<S> for RawRequest
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request(req: Request<Body>, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self(req))
+    }
+}
+
+impl aide::OperationInput for RawRequest {}
+
+/// How a route reads its request body.
+#[derive(Debug, Clone)]
+struct BodyConfig {
+    policy: BodyPolicy,
+    encodings: Arc<[BodyEncoding]>,
+    max_file_size: Option<usize>,
+}
+
+impl BodyConfig {
+    /// Reads the request body and any uploaded files.
+    ///
+    /// The body is decoded with the route encoding matching its `Content-Type`;
+    /// bodies matching none of them are rejected with 415. Empty bodies are
+    /// resolved with [`resolve_body`], building the request value with `empty`.
+    async fn read<T: DeserializeOwned>(
+        &self,
+        request: Request<Body>,
+        empty: fn() -> Option<T>,
+    ) -> Result<(T, Uploads), Error> {
+        if self.policy == BodyPolicy::None {
+            return Ok((resolve_body(None, self.policy, empty)?, Uploads::default()));
+        }
+
+        let encoding = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|content_type| {
+                self.encodings
+                    .iter()
+                    .find(|encoding| encoding.matches(content_type))
+                    .copied()
+            });
+
+        let (body, uploads) = match encoding {
+            Some(BodyEncoding::Multipart) => self.read_multipart(request).await?,
+            encoding => {
+                let bytes = Bytes::from_request(request, &())
+                    .await
+                    .map_err(|rejection| body_error(rejection.status(), rejection.body_text()))?;
+
+                if bytes.is_empty() {
+                    (None, Uploads::default())
+                } else {
+                    let encoding = encoding.ok_or_else(|| self.unsupported_media_type())?;
+                    (Some(decode(encoding, &bytes)?), Uploads::default())
+                }
+            }
+        };
+
+        Ok((resolve_body(body, self.policy, empty)?, uploads))
+    }
+
+    fn unsupported_media_type(&self) -> Error {
+        let accepted: Vec<&str> = self
+            .encodings
+            .iter()
+            .map(BodyEncoding::media_type)
+            .collect();
+
+        Error::unsupported_media_type(
+            "unsupported_media_type",
+            format!(
+                "Unsupported `Content-Type`. Accepted: {}",
+                accepted.join(", ")
+            ),
+        )
+    }
+
+    /// Streams a multipart body, collecting text fields into the request value and
+    /// file parts into [`Uploads`].
+    async fn read_multipart<T: DeserializeOwned>(
+        &self,
+        request: Request<Body>,
+    ) -> Result<(Option<T>, Uploads), Error> {
+        let mut multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|rejection| body_error(rejection.status(), rejection.body_text()))?;
+
+        let mut fields: Vec<(String, String)> = Vec::new();
+        let mut files = Vec::new();
+
+        while let Some(mut field) = multipart
+            .next_field()
+            .await
+            .map_err(|err| body_error(err.status(), err.body_text()))?
+        {
+            let name = field.name().unwrap_or_default().to_string();
+
+            let Some(file_name) = field.file_name().map(str::to_owned) else {
+                let text = field
+                    .text()
+                    .await
+                    .map_err(|err| body_error(err.status(), err.body_text()))?;
+                fields.push((name, text));
+                continue;
+            };
+
+            let content_type = field.content_type().map(str::to_owned);
+            let mut data = Vec::new();
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|err| body_error(err.status(), err.body_text()))?
+            {
+                if let Some(limit) = self.max_file_size {
+                    if data.len() + chunk.len() > limit {
+                        return Err(Error::payload_too_large(
+                            "file_too_large",
+                            format!("File `{}` exceeds the limit of {} bytes", name, limit),
+                        ));
+                    }
+                }
+                data.extend_from_slice(&chunk);
+            }
+
+            files.push(Upload::new(name, Some(file_name), content_type, data));
+        }
+
+        let encoded = serde_urlencoded::to_string(&fields)
+            .map_err(|err| Error::internal("invalid_body", err.to_string()))?;
+        Ok((
+            Some(decode(BodyEncoding::Form, encoded.as_bytes())?),
+            Uploads::new(files),
+        ))
+    }
+}
+
+/// Deserializes a buffered request body.
+///
+/// Every encoding reports malformed input as 400 and well-formed input of the wrong
+/// shape (wrong types, missing fields) as 422, both with the `invalid_body` code.
+fn decode<T: DeserializeOwned>(encoding: BodyEncoding, bytes: &[u8]) -> Result<T, Error> {
+    match encoding {
+        BodyEncoding::Json => decode_json(bytes),
+        // Any byte sequence is a well-formed urlencoded form, so only data errors remain
+        BodyEncoding::Form | BodyEncoding::Multipart => serde_urlencoded::from_bytes(bytes)
+            .map_err(|err| {
+                Error::unprocessable(
+                    "invalid_body",
+                    format!("Failed to deserialize form body: {}", err),
+                )
+            }),
+        #[cfg(feature = "msgpack")]
+        BodyEncoding::MsgPack => rmp_serde::from_slice(bytes).map_err(msgpack_error),
+        #[cfg(feature = "cbor")]
+        BodyEncoding::Cbor => ciborium::from_reader(bytes).map_err(cbor_error),
+    }
+}
+
+#[cfg(feature = "msgpack")]
+fn msgpack_error(err: rmp_serde::decode::Error) -> Error {
+    use rmp_serde::decode::Error as Decode;
+
+    match err {
+        // Serde errors (missing fields, unknown variants) are reported as `Syntax`
+        Decode::TypeMismatch(_)
+        | Decode::OutOfRange
+        | Decode::LengthMismatch(_)
+        | Decode::Syntax(_) => Error::unprocessable(
+            "invalid_body",
+            format!("Failed to deserialize the MessagePack body: {}", err),
+        ),
+        _ => Error::bad_request(
+            "invalid_body",
+            format!("Failed to parse the MessagePack body: {}", err),
+        ),
+    }
+}
+
+#[cfg(feature = "cbor")]
+fn cbor_error(err: ciborium::de::Error<std::io::Error>) -> Error {
+    match err {
+        ciborium::de::Error::Semantic(..) => Error::unprocessable(
+            "invalid_body",
+            format!("Failed to deserialize the CBOR body: {}", err),
+        ),
+        _ => Error::bad_request(
+            "invalid_body",
+            format!("Failed to parse the CBOR body: {}", err),
+        ),
+    }
+}
+
+/// Deserializes a JSON body, reporting the path of the offending field.
+///
+/// Syntax errors are reported as 400 and data errors (wrong types, missing fields)
+/// as 422, both with `invalid_body` and the field path, line and column in `details`.
+fn decode_json<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Error> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let value = serde_path_to_error::deserialize(&mut deserializer).map_err(|err| {
+        let path = err.path().to_string();
+        json_error(err.into_inner(), &path)
+    })?;
+    deserializer.end().map_err(|err| json_error(err, "."))?;
+    Ok(value)
+}
+
+fn json_error(err: serde_json::Error, path: &str) -> Error {
+    let details = serde_json::json!({
+        "path": path,
+        "line": err.line(),
+        "column": err.column(),
+    });
+
+    match err.classify() {
+        serde_json::error::Category::Data => Error::unprocessable_with_details(
+            "invalid_body",
+            format!("Failed to deserialize the JSON body: {}", err),
+            details,
+        ),
+        _ => Error::bad_request_with_details(
+            "invalid_body",
+            format!("Failed to parse the JSON body: {}", err),
+            details,
+        ),
+    }
+}
+
+/// Converts an axum body rejection into an [`Error`] with the same status code.
+fn body_error(status: StatusCode, message: String) -> Error {
+    match status {
+        StatusCode::PAYLOAD_TOO_LARGE => Error::payload_too_large("payload_too_large", message),
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => {
+            Error::unsupported_media_type("unsupported_media_type", message)
+        }
+        StatusCode::UNPROCESSABLE_ENTITY => Error::unprocessable("invalid_body", message),
+        status if status.is_client_error() => Error::bad_request("invalid_body", message),
+        _ => Error::internal("invalid_body", message),
+    }
+}
+
+/// Hook rewriting framework-generated errors, set with [`ServerBuilder::map_rejection()`].
+type RejectionHook = Arc<dyn Fn(Error) -> Error + Send + Sync>;
+
+/// Error generated by uncovr itself rather than by a handler.
+///
+/// Rendered like [`Error`], but also stored in the response extensions so the
+/// rejection hook can rewrite it.
+#[derive(Debug, Clone)]
+struct Rejection(Error);
+
+impl From<Error> for Rejection {
+    fn from(error: Error) -> Self {
+        Self(error)
+    }
+}
+
+impl IntoResponse for Rejection {
+    fn into_response(self) -> Response<Body> {
+        let mut response = self.0.clone().into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+impl aide::OperationOutput for Rejection {
+    type Inner = Error;
+
+    fn operation_response(
+        ctx: &mut aide::r#gen::GenContext,
+        operation: &mut Operation,
+    ) -> Option<aide::openapi::Response> {
+        Error::operation_response(ctx, operation)
+    }
+
+    /// Framework errors depend on the route's parameters and body, so they are
+    /// documented by [`OperationDocs::apply()`] instead.
+    fn inferred_responses(
+        _ctx: &mut aide::r#gen::GenContext,
+        _operation: &mut Operation,
+    ) -> Vec<(Option<u16>, aide::openapi::Response)> {
+        Vec::new()
+    }
+}
+
+/// Rewrites a framework-generated error response with the rejection hook.
+///
+/// Only the status and body are replaced, so headers such as `Allow` are kept.
+/// The rewritten error's details are carried over for problem details rendering.
+fn apply_rejection_hook(hook: &RejectionHook, mut response: Response<Body>) -> Response<Body> {
+    let Some(Rejection(error)) = response.extensions_mut().remove::<Rejection>() else {
+        return response;
+    };
+
+    let mut rewritten = hook(error).into_response();
+    if let Some(info) = rewritten.extensions_mut().remove::<ErrorInfo>() {
+        response.extensions_mut().insert(info);
+    }
+    *response.status_mut() = rewritten.status();
+    *response.body_mut() = rewritten.into_body();
+    response.headers_mut().remove(http::header::CONTENT_LENGTH);
+    response
+}
+
+/// Logs a warning when a handler responds with an error status its [`Meta`] did not declare.
+///
+/// Errors generated by uncovr itself are not checked.
+///
+/// [`Meta`]: crate::server::Meta
+fn warn_undeclared_error(response: &Response<Body>, declared: &[StatusCode], route: &str) {
+    let status = response.status();
+    if (status.is_client_error() || status.is_server_error())
+        && !declared.contains(&status)
+        && response.extensions().get::<Rejection>().is_none()
+    {
+        tracing::warn!(
+            "Route `{}` returned {}, which is not declared in its `Meta::errors()`",
+            route,
+            status,
+        );
+    }
+}
+
+/// Fallback for requests that match no route.
+async fn route_not_found(method: Method, uri: Uri) -> Rejection {
+    Error::not_found(
+        "not_found",
+        format!("No route matches `{} {}`", method, uri.path()),
+    )
+    .into()
+}
+
+/// Fallback for requests whose path matches a route but whose method does not.
+async fn method_not_allowed(method: Method, uri: Uri) -> Rejection {
+    Error::method_not_allowed(
+        "method_not_allowed",
+        format!("Method `{}` is not allowed on `{}`", method, uri.path()),
+    )
+    .into()
+}
+
+/// Renders the empty 404 and 405 responses of axum's default fallbacks as [`Error`] bodies.
+///
+/// Applied as a layer rather than installed as router fallbacks, so built routers keep
+/// axum's default fallbacks and can still be merged. Requests that matched no route carry
+/// no [`MatchedPath`]; responses with a body, from handlers or custom fallbacks, are kept.
+async fn routing_error(
+    request: Request<Body>,
+    next: axum::middleware::Next,
+    not_found: bool,
+) -> Response<Body> {
+    use axum::body::HttpBody;
+
+    let method = request.method().clone();
+    let uri = request.uri().clone();
+    let matched = request.extensions().get::<MatchedPath>().is_some();
+    let response = next.run(request).await;
+
+    let empty = response.body().size_hint().exact() == Some(0)
+        && !response.headers().contains_key(CONTENT_TYPE);
+    match response.status() {
+        StatusCode::NOT_FOUND if empty && not_found && !matched => {
+            route_not_found(method, uri).await.into_response()
+        }
+        StatusCode::METHOD_NOT_ALLOWED if empty => {
+            let mut rejection = method_not_allowed(method, uri).await.into_response();
+            if let Some(allow) = response.headers().get(ALLOW) {
+                rejection.headers_mut().insert(ALLOW, allow.clone());
+            }
+            rejection
+        }
+        _ => response,
+    }
+}
+
+/// Documents the request body of an operation under the media type of every accepted encoding.
+///
+/// All encodings share the request type's JSON schema.
+fn document_body<T: JsonSchema>(operation: &mut Operation, encodings: &[BodyEncoding]) {
+    aide::r#gen::in_context(|ctx| {
+        <axum::Json<T> as aide::OperationInput>::operation_input(ctx, operation)
+    });
+
+    if let Some(ReferenceOr::Item(body)) = operation.request_body.as_mut() {
+        let Some(media) = body.content.values().next().cloned() else {
+            return;
+        };
+        body.content = encodings
+            .iter()
+            .map(|encoding| (encoding.media_type().to_string(), media.clone()))
+            .collect();
+    }
+}
+
+/// Returns whether a response type documents a JSON success response.
+///
+/// Such endpoints reject requests that accept no negotiable format before running.
+fn documents_json<T: aide::OperationOutput>() -> bool {
+    aide::r#gen::in_context(|ctx| {
+        T::inferred_responses(ctx, &mut Operation::default())
+            .iter()
+            .filter(|(status, _)| status.is_none_or(|status| status < 400))
+            .any(|(_, response)| {
+                response
+                    .content
+                    .keys()
+                    .any(|media| is_json_media_type(media))
+            })
+    })
+}
+
+/// Documents the [`Error`] schema under `status` if the operation has no response for it yet.
+fn document_error(operation: &mut Operation, status: StatusCode) {
+    let responses = operation.responses.get_or_insert_with(Default::default);
+    let code = aide::openapi::StatusCode::Code(status.as_u16());
+    if !responses.responses.contains_key(&code) {
+        let response = aide::r#gen::in_context(|ctx| Error::openapi_response(ctx, status));
+        responses
+            .responses
+            .insert(code, ReferenceOr::Item(response));
+    }
+}
+
+/// Returns whether a documented status code is a success or redirect (below 400).
+fn is_success_status(status: &aide::openapi::StatusCode) -> bool {
+    match status {
+        aide::openapi::StatusCode::Code(code) => *code < 400,
+        aide::openapi::StatusCode::Range(range) => *range < 4,
+    }
+}
+
+/// Replaces the inferred 2xx and 3xx responses with a single response under `status`.
+///
+/// The first inferred response provides the body schema, which is dropped for
+/// statuses that have no body.
+fn document_success_status(operation: &mut Operation, status: StatusCode) {
+    let responses = operation.responses.get_or_insert_with(Default::default);
+    let inferred = std::mem::take(&mut responses.responses);
+
+    let mut response = inferred
+        .iter()
+        .filter(|(code, _)| is_success_status(code))
+        .find_map(|(_, response)| response.as_item().cloned())
+        .unwrap_or_default();
+    response.description = status.canonical_reason().unwrap_or_default().to_string();
+    if status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+        || status.is_redirection()
+    {
+        response.content.clear();
+    }
+
+    responses.responses = std::iter::once((
+        aide::openapi::StatusCode::Code(status.as_u16()),
+        ReferenceOr::Item(response),
+    ))
+    .chain(
+        inferred
+            .into_iter()
+            .filter(|(code, _)| !is_success_status(code)),
+    )
+    .collect();
+}
+
+/// Documents a header on every success and redirect response of an operation.
+fn document_response_header(operation: &mut Operation, header: &ResponseHeader) {
+    let Some(responses) = operation.responses.as_mut() else {
+        return;
+    };
+
+    let documented = aide::openapi::Header {
+        description: header.description.map(str::to_string),
+        style: Default::default(),
+        required: false,
+        deprecated: None,
+        format: ParameterSchemaOrContent::Schema(SchemaObject {
+            json_schema: ParamSchema::default().to_schema_object().into(),
+            external_docs: None,
+            example: None,
+        }),
+        example: None,
+        examples: Default::default(),
+        extensions: Default::default(),
+    };
+
+    for (_, response) in responses
+        .responses
+        .iter_mut()
+        .filter(|(code, _)| is_success_status(code))
+    {
+        if let ReferenceOr::Item(response) = response {
+            response.headers.insert(
+                header.name.to_string(),
+                ReferenceOr::Item(documented.clone()),
+            );
+        }
+    }
+}
+
+/// Builds the request value used when no body was sent.
+///
+/// Tries JSON `null` first (for `()` and `Option<T>`), then an empty object
+/// (for maps and structs with `#[serde(default)]`).
+fn empty_request<T: DeserializeOwned>() -> Option<T> {
+    serde_json::from_value(serde_json::Value::Null)
+        .or_else(|_| serde_json::from_value(serde_json::Value::Object(Default::default())))
+        .ok()
+}
+
+/// Builds the request value used when no body was sent from the type's [`Default`].
+fn default_request<T: Default>() -> Option<T> {
+    Some(T::default())
+}
+
+/// Resolves the handler's request value from an extracted body according to the policy.
+///
+/// Registration ensures `empty` succeeds for routes that never read a body.
+fn resolve_body<T>(
+    body: Option<T>,
+    policy: BodyPolicy,
+    empty: fn() -> Option<T>,
+) -> Result<T, Error> {
+    match (body, policy) {
+        (Some(body), _) => Ok(body),
+        (None, BodyPolicy::Required) => Err(missing_body()),
+        (None, BodyPolicy::None | BodyPolicy::Optional) => empty().ok_or_else(missing_body),
     }
 }
 
+fn missing_body() -> Error {
+    Error::bad_request("missing_body", "Request body is required")
+}
+
 /// Custom request span maker for structured logging.
 ///
 /// Creates tracing spans for HTTP requests with method and path information.
@@ -152,9 +770,13 @@ impl Server {
         tracing::info!("Server running: http://{}", self.address);
         tracing::info!("Press Ctrl+C to stop");
 
-        axum::serve(listener, self.router.into_make_service())
-            .await
-            .map_err(std::io::Error::other)
+        axum::serve(
+            listener,
+            self.router
+                .into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(std::io::Error::other)
     }
 }
 
@@ -208,6 +830,15 @@ pub struct ServerBuilder {
     openapi: Option<aide::openapi::OpenApi>,
     config: Option<App>,
     logging: Option<crate::config::Logging>,
+    allowed_methods: AllowedMethods,
+    extension_routes: Vec<(&'static str, Method, axum::routing::MethodRouter)>,
+    content_negotiation: bool,
+    rejection_hook: Option<RejectionHook>,
+    problem_details: bool,
+    #[cfg(feature = "secure-cookies")]
+    cookie_key: Option<crate::server::cookies::Key>,
+    session_scheme: Option<SecurityScheme>,
+    has_fallback: bool,
 }
 
 impl Default for ServerBuilder {
@@ -218,8 +849,149 @@ impl Default for ServerBuilder {
             openapi: None,
             config: None,
             logging: None,
+            allowed_methods: Default::default(),
+            extension_routes: Vec::new(),
+            content_negotiation: false,
+            rejection_hook: None,
+            problem_details: false,
+            #[cfg(feature = "secure-cookies")]
+            cookie_key: None,
+            session_scheme: None,
+            has_fallback: false,
+        }
+    }
+}
+
+/// Returns every documented response in the specification with its status code.
+///
+/// The status code is `None` for default responses.
+fn responses_mut(
+    api: &mut aide::openapi::OpenApi,
+) -> impl Iterator<
+    Item = (
+        Option<&aide::openapi::StatusCode>,
+        &mut aide::openapi::Response,
+    ),
+> {
+    api.paths
+        .iter_mut()
+        .flat_map(|paths| paths.paths.values_mut())
+        .filter_map(|item| match item {
+            ReferenceOr::Item(item) => Some(item),
+            ReferenceOr::Reference { .. } => None,
+        })
+        .flat_map(|item| {
+            [
+                &mut item.get,
+                &mut item.put,
+                &mut item.post,
+                &mut item.delete,
+                &mut item.options,
+                &mut item.head,
+                &mut item.patch,
+                &mut item.trace,
+            ]
+            .into_iter()
+            .flatten()
+        })
+        .filter_map(|operation| operation.responses.as_mut())
+        .flat_map(|responses| {
+            let default = responses
+                .default
+                .iter_mut()
+                .map(|response| (None, response));
+            let by_status = responses
+                .responses
+                .iter_mut()
+                .map(|(status, response)| (Some(status), response));
+            default.chain(by_status)
+        })
+        .filter_map(|(status, response)| match response {
+            ReferenceOr::Item(response) => Some((status, response)),
+            ReferenceOr::Reference { .. } => None,
+        })
+}
+
+/// Documents every enabled [`ResponseFormat`] alongside JSON response bodies.
+fn add_response_formats(api: &mut aide::openapi::OpenApi) {
+    for (_, response) in responses_mut(api) {
+        let Some(json) = response.content.get("application/json").cloned() else {
+            continue;
+        };
+        for format in ResponseFormat::ALL {
+            response
+                .content
+                .entry(format.media_type().to_string())
+                .or_insert_with(|| json.clone());
+        }
+    }
+}
+
+/// Documents JSON error responses as [`ProblemDetails`].
+fn use_problem_details(api: &mut aide::openapi::OpenApi) {
+    let problem = MediaType {
+        schema: Some(SchemaObject {
+            json_schema: schemars::schema::Schema::new_ref(
+                "#/components/schemas/ProblemDetails".to_string(),
+            ),
+            example: None,
+            external_docs: None,
+        }),
+        ..Default::default()
+    };
+
+    for (status, response) in responses_mut(api) {
+        let is_error = match status {
+            Some(aide::openapi::StatusCode::Code(code)) => *code >= 400,
+            Some(aide::openapi::StatusCode::Range(range)) => *range >= 4,
+            None => false,
+        };
+        if is_error && response.content.shift_remove("application/json").is_some() {
+            response
+                .content
+                .insert(ProblemDetails::MEDIA_TYPE.to_string(), problem.clone());
         }
     }
+
+    let schema = schemars::r#gen::SchemaSettings::openapi3()
+        .into_generator()
+        .into_root_schema_for::<ProblemDetails>()
+        .schema;
+    api.components
+        .get_or_insert_with(Default::default)
+        .schemas
+        .insert(
+            "ProblemDetails".to_string(),
+            SchemaObject {
+                json_schema: schemars::schema::Schema::Object(schema),
+                example: None,
+                external_docs: None,
+            },
+        );
+}
+
+/// Methods registered for each route path, shared with OPTIONS handlers.
+///
+/// Filled during registration and read while serving, so OPTIONS responses
+/// list endpoints registered after them as well.
+type AllowedMethods = Arc<RwLock<HashMap<&'static str, Vec<Method>>>>;
+
+/// Builds the `Allow` header value for a route path.
+///
+/// `HEAD` is included whenever `GET` is registered, since axum answers it
+/// with the GET handler.
+fn allow_header(allowed_methods: &AllowedMethods, path: &str) -> Option<HeaderValue> {
+    let allowed_methods = allowed_methods.read().unwrap();
+    let methods = allowed_methods.get(path)?;
+
+    let mut names: Vec<&str> = methods.iter().map(Method::as_str).collect();
+    if methods.contains(&Method::GET) && !methods.contains(&Method::HEAD) {
+        names.push(Method::HEAD.as_str());
+    }
+    names.sort_unstable();
+    names.dedup();
+
+    HeaderValue::from_str(&names.join(", ")).ok()
 }
 
 /// Parameter information for OpenAPI documentation (internal helper).
@@ -235,56 +1007,577 @@ struct ParamInfo {
     description: Option<&'static str>,
     /// Whether the parameter is required
     required: bool,
+    /// Schema information for the parameter value
+    schema: ParamSchema,
+    /// How the value is written in the query string; `Form` for path parameters
+    style: ParamStyle,
 }
 
-/// Helper function to convert ParamInfo to aide's Parameter type for OpenAPI.
+/// Documentation collected from an endpoint for its OpenAPI operation (internal helper).
 ///
-/// Transforms uncovr's parameter representation into OpenAPI 3.0 parameter specifications
-/// for automatic documentation generation. This keeps the high-level uncovr API simple
-/// while ensuring proper OpenAPI schema generation.
-fn param_info_to_query_param(param: &ParamInfo) -> ReferenceOr<Parameter> {
-    ReferenceOr::Item(Parameter::Query {
-        parameter_data: ParameterData {
-            name: param.name.to_string(),
-            description: param.description.map(|s| s.to_string()),
-            required: param.required,
-            deprecated: None,
-            format: ParameterSchemaOrContent::Schema(SchemaObject {
-                json_schema: serde_json::from_value(serde_json::json!({ "type": "string" }))
-                    .unwrap(),
-                external_docs: None,
-                example: None,
-            }),
-            example: None,
-            examples: Default::default(),
-            explode: None,
-            extensions: Default::default(),
-        },
-        allow_reserved: false,
-        style: QueryStyle::Form,
-        allow_empty_value: None,
-    })
+/// Gathers everything [`ServerBuilder::register`] needs from [`Route`] and
+/// [`Meta`](crate::server::Meta) so the operation transform is written once,
+/// regardless of the HTTP method being registered.
+struct OperationDocs {
+    params: RouteParams,
+    filter: Option<Arc<FilterSpec>>,
+    path_params: Vec<ParamInfo>,
+    query_params: Vec<ParamInfo>,
+    header_params: Vec<ParamInfo>,
+    cookie_params: Vec<ParamInfo>,
+    summary: &'static str,
+    description: Option<&'static str>,
+    tags: Vec<&'static str>,
+    security: Vec<SecurityScheme>,
+    body: BodyConfig,
+    document_body: fn(&mut Operation, &[BodyEncoding]),
+    errors: Option<Vec<DeclaredError>>,
+    success_status: Option<StatusCode>,
+    response_headers: Vec<ResponseHeader>,
+    response_config: Option<ResponseCallback>,
 }
 
-/// Convert uncovr SecurityScheme to OpenAPI security requirement name.
-///
-/// Maps security schemes to their standard OpenAPI security scheme names.
-/// These names are used to reference security schemes defined in the OpenAPI spec.
-fn security_scheme_name(scheme: &SecurityScheme) -> &'static str {
-    match scheme {
-        SecurityScheme::Bearer => "bearerAuth",
-        SecurityScheme::Basic => "basicAuth",
-        SecurityScheme::ApiKey { .. } => "apiKeyAuth",
-        SecurityScheme::OAuth2 { .. } => "oauth2Auth",
+impl OperationDocs {
+    /// Applies the collected documentation to an aide operation.
+    fn apply(self, mut op: TransformOperation<'_>) -> TransformOperation<'_> {
+        // Typed parameter structs are documented first; individually declared
+        // parameters with the same name are skipped to avoid duplicates.
+        if let Some(typed) = self.params.typed_path {
+            (typed.document)(op.inner_mut());
+        }
+
+        if let Some(typed) = self.params.typed_query {
+            (typed.document)(op.inner_mut());
+        }
+
+        let params = self
+            .path_params
+            .iter()
+            .map(param_info_to_path_param)
+            .chain(self.query_params.iter().map(param_info_to_query_param))
+            .chain(self.header_params.iter().map(param_info_to_header_param))
+            .chain(self.cookie_params.iter().map(param_info_to_cookie_param));
+
+        for param in params {
+            if !has_parameter(op.inner_mut(), &param) {
+                op.inner_mut().parameters.push(param);
+            }
+        }
+
+        if let Some(filter) = &self.filter {
+            filter.document(op.inner_mut());
+        }
+
+        if self.body.policy != BodyPolicy::None {
+            (self.document_body)(op.inner_mut(), &self.body.encodings);
+            if let Some(ReferenceOr::Item(body)) = op.inner_mut().request_body.as_mut() {
+                body.required = self.body.policy == BodyPolicy::Required;
+            }
+        }
+
+        // Declared errors replace the error responses inferred from the handler's
+        // response type; framework errors below are still documented.
+        if self.errors.is_some() {
+            if let Some(responses) = op.inner_mut().responses.as_mut() {
+                responses.responses.retain(|code, _| match code {
+                    aide::openapi::StatusCode::Code(code) => *code < 400,
+                    aide::openapi::StatusCode::Range(range) => *range < 4,
+                });
+            }
+        }
+
+        if let Some(status) = self.success_status {
+            document_success_status(op.inner_mut(), status);
+        }
+        for header in &self.response_headers {
+            document_response_header(op.inner_mut(), header);
+        }
+
+        // Document the errors uncovr itself returns before the handler runs,
+        // unless the handler's response type already documents the status.
+        let mut rejections = Vec::new();
+        if !op.inner_mut().parameters.is_empty() {
+            rejections.push(StatusCode::BAD_REQUEST);
+        }
+        if !self.params.checks.is_empty() {
+            rejections.push(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+        if self.body.policy != BodyPolicy::None {
+            rejections.extend([
+                StatusCode::BAD_REQUEST,
+                StatusCode::PAYLOAD_TOO_LARGE,
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ]);
+        }
+        for status in rejections {
+            document_error(op.inner_mut(), status);
+        }
+
+        for declared in self.errors.iter().flatten() {
+            let mut response =
+                aide::r#gen::in_context(|ctx| Error::openapi_response(ctx, declared.status));
+            if let Some(description) = declared.description {
+                response.description = description.to_string();
+            }
+            op.inner_mut()
+                .responses
+                .get_or_insert_with(Default::default)
+                .responses
+                .insert(
+                    aide::openapi::StatusCode::Code(declared.status.as_u16()),
+                    ReferenceOr::Item(response),
+                );
+        }
+
+        op = op.summary(self.summary);
+        if let Some(desc) = self.description {
+            op = op.description(desc);
+        }
+
+        for tag in &self.tags {
+            op = op.tag(tag);
+        }
+
+        // Add security requirements
+        for scheme in &self.security {
+            let mut security_req = SecurityRequirement::new();
+            security_req.insert(security_scheme_name(scheme).to_string(), vec![]);
+            op.inner_mut().security.push(security_req);
+        }
+
+        // Apply response config callback if provided
+        if let Some(callback) = self.response_config {
+            op = callback(op);
+        }
+
+        op
     }
 }
 
-/// Convert uncovr SecurityScheme to OpenAPI SecurityScheme.
+/// Typed parameter structs and parameter constraints declared on a route (internal helper).
 ///
-/// Transforms uncovr's security scheme definitions into OpenAPI 3.0 security scheme
-/// specifications for automatic documentation generation.
-fn to_openapi_security_scheme(scheme: &SecurityScheme) -> OpenApiSecurityScheme {
-    match scheme {
+/// Cloned into every request handler so the constraints can be checked and the structs
+/// deserialized before the endpoint's `Handler::handle()` runs.
+#[derive(Clone)]
+struct RouteParams {
+    typed_query: Option<TypedParams>,
+    typed_path: Option<TypedParams>,
+    checks: Arc<[ParamCheck]>,
+}
+
+impl RouteParams {
+    /// Builds the handler's [`Path`] and [`Query`], deserializing any typed parameter structs.
+    ///
+    /// Missing required parameters and constraint violations are reported as a 422
+    /// `validation_failed` error and deserialization failures as 400 Bad Request errors.
+    fn extract(
+        &self,
+        path_params: HashMap<String, String>,
+        uri: &Uri,
+        headers: &HeaderMap,
+    ) -> Result<(Path, Query), Error> {
+        let mut path = Path::new(path_params);
+        let mut query = Query::from_query_string(uri.query().unwrap_or_default());
+        let has_cookies = self
+            .checks
+            .iter()
+            .any(|check| matches!(check.location, ParamLocation::Cookie));
+        let cookies = if has_cookies {
+            Cookies::from_headers(headers)
+        } else {
+            Cookies::default()
+        };
+
+        let sources = ParamSources {
+            path: &path,
+            query: &query,
+            headers,
+            cookies: &cookies,
+        };
+        let mut violations = BTreeMap::new();
+        for check in self.checks.iter() {
+            let messages = check.check(&sources);
+            if !messages.is_empty() {
+                violations.insert(check.name, messages);
+            }
+        }
+        if !violations.is_empty() {
+            return Err(Error::unprocessable_with_details(
+                "validation_failed",
+                "Request validation failed",
+                violations,
+            ));
+        }
+
+        if let Some(typed) = self.typed_path {
+            let value = (typed.decode)(&path.to_urlencoded()).map_err(|err| {
+                Error::bad_request(
+                    "invalid_path",
+                    format!("Failed to deserialize path parameters: {}", err),
+                )
+            })?;
+            path = path.with_typed(value);
+        }
+
+        if let Some(typed) = self.typed_query {
+            let value = (typed.decode)(uri.query().unwrap_or_default()).map_err(|err| {
+                Error::bad_request(
+                    "invalid_query",
+                    format!("Failed to deserialize query string: {}", err),
+                )
+            })?;
+            query = query.with_typed(value);
+        }
+
+        Ok((path, query))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum ParamLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+/// Request values read by [`ParamCheck`].
+struct ParamSources<'a> {
+    path: &'a Path,
+    query: &'a Query,
+    headers: &'a HeaderMap,
+    cookies: &'a Cookies,
+}
+
+/// Constraints on a single declared route parameter, checked on every request.
+struct ParamCheck {
+    name: &'static str,
+    location: ParamLocation,
+    style: ParamStyle,
+    required: bool,
+    schema: ParamSchema,
+    /// Numeric bounds from the builder and the parameter's JSON schema
+    bounds: (Option<f64>, Option<f64>),
+    pattern: Option<regex::Regex>,
+}
+
+impl ParamCheck {
+    /// Builds the check for a declared parameter, or `None` if it has no constraints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parameter's pattern is not a valid regular expression.
+    fn new(param: &ParamInfo, location: ParamLocation) -> Option<Self> {
+        // Path parameters are always present when the route matches
+        let required = param.required && !matches!(location, ParamLocation::Path);
+        if !required && !param.schema.has_constraints() {
+            return None;
+        }
+
+        let pattern = param.schema.pattern.as_deref().map(|pattern| {
+            regex::Regex::new(pattern).unwrap_or_else(|err| {
+                panic!("Invalid pattern for parameter `{}`: {}", param.name, err)
+            })
+        });
+
+        Some(Self {
+            name: param.name,
+            location,
+            style: param.style,
+            required,
+            schema: param.schema.clone(),
+            bounds: param.schema.bounds(),
+            pattern,
+        })
+    }
+
+    /// Returns a message for each constraint the parameter's values violate.
+    ///
+    /// Repeated and comma-separated query parameters are checked value by value;
+    /// only presence is checked for deep object parameters.
+    fn check(&self, sources: &ParamSources<'_>) -> Vec<String> {
+        let query = sources.query;
+        let values = match (self.location, self.style) {
+            (ParamLocation::Path, _) => sources.path.get(self.name).into_iter().collect(),
+            (ParamLocation::Header, _) => sources
+                .headers
+                .get(self.name)
+                .and_then(|value| value.to_str().ok())
+                .into_iter()
+                .collect(),
+            (ParamLocation::Cookie, _) => sources.cookies.value(self.name).into_iter().collect(),
+            (ParamLocation::Query, ParamStyle::Form) => query.get(self.name).into_iter().collect(),
+            (ParamLocation::Query, ParamStyle::Repeated) => query.get_all(self.name),
+            (ParamLocation::Query, ParamStyle::CommaSeparated) => query.get_list(self.name),
+            (ParamLocation::Query, ParamStyle::DeepObject) => {
+                return match self.required && query.get_object(self.name).is_none() {
+                    true => vec!["Parameter is required".to_string()],
+                    false => Vec::new(),
+                };
+            }
+        };
+
+        if values.is_empty() {
+            return match self.required {
+                true => vec!["Parameter is required".to_string()],
+                false => Vec::new(),
+            };
+        }
+
+        let mut messages: Vec<String> = Vec::new();
+        for value in values {
+            for message in self.messages(value) {
+                if !messages.contains(&message) {
+                    messages.push(message);
+                }
+            }
+        }
+        messages
+    }
+
+    /// Returns a message for each constraint a single value violates.
+    fn messages(&self, value: &str) -> Vec<String> {
+        let schema = &self.schema;
+        let mut messages = Vec::new();
+
+        let type_error = match schema.value_type() {
+            Some(InstanceType::Integer)
+                if value.parse::<i64>().is_err() && value.parse::<u64>().is_err() =>
+            {
+                Some("Must be an integer")
+            }
+            Some(InstanceType::Number) if value.parse::<f64>().is_err() => Some("Must be a number"),
+            Some(InstanceType::Boolean) if value.parse::<bool>().is_err() => {
+                Some("Must be true or false")
+            }
+            _ => None,
+        };
+        if let Some(error) = type_error {
+            return vec![error.to_string()];
+        }
+
+        if let Some(values) = &schema.enum_values {
+            let accepted: Vec<String> = values
+                .iter()
+                .map(|value| match value {
+                    serde_json::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                })
+                .collect();
+            if !accepted.iter().any(|accepted| accepted == value) {
+                messages.push(format!("Must be one of: {}", accepted.join(", ")));
+            }
+        }
+
+        let (minimum, maximum) = &self.bounds;
+        if minimum.is_some() || maximum.is_some() {
+            match value.parse::<f64>() {
+                Ok(number) => {
+                    if let Some(min) = minimum.filter(|min| number < *min) {
+                        messages.push(format!("Must be at least {}", min));
+                    }
+                    if let Some(max) = maximum.filter(|max| number > *max) {
+                        messages.push(format!("Must be at most {}", max));
+                    }
+                }
+                Err(_) => messages.push("Must be a number".to_string()),
+            }
+        }
+
+        let length = value.chars().count();
+        if let Some(min) = schema.min_length.filter(|min| length < *min as usize) {
+            messages.push(format!("Must be at least {} characters long", min));
+        }
+        if let Some(max) = schema.max_length.filter(|max| length > *max as usize) {
+            messages.push(format!("Must be at most {} characters long", max));
+        }
+
+        if let Some(pattern) = self.pattern.as_ref().filter(|re| !re.is_match(value)) {
+            messages.push(format!("Must match the pattern `{}`", pattern.as_str()));
+        }
+
+        messages
+    }
+}
+
+/// Returns true if the operation already documents a parameter with the same name and location.
+fn has_parameter(operation: &aide::openapi::Operation, param: &ReferenceOr<Parameter>) -> bool {
+    let ReferenceOr::Item(param) = param else {
+        return false;
+    };
+
+    operation.parameters.iter().any(|existing| match existing {
+        ReferenceOr::Item(existing) => {
+            std::mem::discriminant(existing) == std::mem::discriminant(param)
+                && existing.parameter_data_ref().name == param.parameter_data_ref().name
+        }
+        ReferenceOr::Reference { .. } => false,
+    })
+}
+
+/// Helper function to convert ParamInfo to aide's ParameterData for OpenAPI.
+///
+/// Transforms uncovr's parameter representation into the data shared by all OpenAPI 3.0
+/// parameter locations. This keeps the high-level uncovr API simple while ensuring proper
+/// OpenAPI schema generation.
+fn param_info_to_parameter_data(param: &ParamInfo) -> ParameterData {
+    ParameterData {
+        name: param.name.to_string(),
+        description: param.description.map(|s| s.to_string()),
+        required: param.required,
+        deprecated: None,
+        format: ParameterSchemaOrContent::Schema(SchemaObject {
+            json_schema: param.schema.to_schema_object().into(),
+            external_docs: None,
+            example: None,
+        }),
+        example: param.schema.example.clone(),
+        examples: Default::default(),
+        explode: None,
+        extensions: Default::default(),
+    }
+}
+
+/// Helper function to convert ParamInfo to an OpenAPI query parameter.
+fn param_info_to_query_param(param: &ParamInfo) -> ReferenceOr<Parameter> {
+    let (style, explode) = match param.style {
+        ParamStyle::Form => (QueryStyle::Form, None),
+        ParamStyle::Repeated => (QueryStyle::Form, Some(true)),
+        ParamStyle::CommaSeparated => (QueryStyle::Form, Some(false)),
+        ParamStyle::DeepObject => (QueryStyle::DeepObject, Some(true)),
+    };
+    let mut parameter_data = param_info_to_parameter_data(param);
+    parameter_data.explode = explode;
+
+    ReferenceOr::Item(Parameter::Query {
+        parameter_data,
+        allow_reserved: false,
+        style,
+        allow_empty_value: None,
+    })
+}
+
+/// Helper function to convert ParamInfo to an OpenAPI header parameter.
+fn param_info_to_header_param(param: &ParamInfo) -> ReferenceOr<Parameter> {
+    ReferenceOr::Item(Parameter::Header {
+        parameter_data: param_info_to_parameter_data(param),
+        style: HeaderStyle::Simple,
+    })
+}
+
+/// Helper function to convert ParamInfo to an OpenAPI cookie parameter.
+fn param_info_to_cookie_param(param: &ParamInfo) -> ReferenceOr<Parameter> {
+    ReferenceOr::Item(Parameter::Cookie {
+        parameter_data: param_info_to_parameter_data(param),
+        style: CookieStyle::Form,
+    })
+}
+
+/// Helper function to convert ParamInfo to an OpenAPI path parameter.
+///
+/// Path parameters are always emitted as required, as mandated by the OpenAPI specification.
+fn param_info_to_path_param(param: &ParamInfo) -> ReferenceOr<Parameter> {
+    let mut parameter_data = param_info_to_parameter_data(param);
+    parameter_data.required = true;
+
+    ReferenceOr::Item(Parameter::Path {
+        parameter_data,
+        style: PathStyle::Simple,
+    })
+}
+
+/// Returns the parameter names captured by a route template.
+///
+/// Both named captures (`/users/:id`) and wildcards (`/files/*path`) are returned,
+/// in the order they appear in the path.
+fn path_template_params(path: &'static str) -> Vec<&'static str> {
+    path.split('/')
+        .filter_map(|segment| {
+            segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('*'))
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Resolves the documented path parameters for a route.
+///
+/// When the route declares no path parameters, they are inferred from the path template
+/// without descriptions. Otherwise the declared names must exactly match the captures in
+/// the template, so that the generated documentation cannot drift from the actual routing.
+fn resolve_path_params(route: &Route) -> Result<Vec<ParamInfo>, String> {
+    let template_params = path_template_params(route.path);
+
+    if route.path_params.is_empty() {
+        return Ok(template_params
+            .into_iter()
+            .map(|name| ParamInfo {
+                name,
+                description: None,
+                required: true,
+                schema: ParamSchema::default(),
+                style: ParamStyle::Form,
+            })
+            .collect());
+    }
+
+    for param in &route.path_params {
+        if !template_params.contains(&param.name) {
+            return Err(format!(
+                "Route `{} {}` declares path parameter `{}` which does not appear in the path",
+                route.method.as_str().to_uppercase(),
+                route.path,
+                param.name
+            ));
+        }
+    }
+
+    for name in &template_params {
+        if !route.path_params.iter().any(|param| param.name == *name) {
+            return Err(format!(
+                "Route `{} {}` captures path parameter `{}` which is not declared on the route",
+                route.method.as_str().to_uppercase(),
+                route.path,
+                name
+            ));
+        }
+    }
+
+    Ok(route
+        .path_params
+        .iter()
+        .map(|param| ParamInfo {
+            name: param.name,
+            description: param.description,
+            required: true,
+            schema: param.schema.clone(),
+            style: ParamStyle::Form,
+        })
+        .collect())
+}
+
+/// Convert uncovr SecurityScheme to OpenAPI security requirement name.
+///
+/// Maps security schemes to their standard OpenAPI security scheme names.
+/// These names are used to reference security schemes defined in the OpenAPI spec.
+fn security_scheme_name(scheme: &SecurityScheme) -> &'static str {
+    match scheme {
+        SecurityScheme::Bearer => "bearerAuth",
+        SecurityScheme::Basic => "basicAuth",
+        SecurityScheme::ApiKey { .. } => "apiKeyAuth",
+        SecurityScheme::Session { .. } => "sessionAuth",
+        SecurityScheme::OAuth2 { .. } => "oauth2Auth",
+    }
+}
+
+/// Convert uncovr SecurityScheme to OpenAPI SecurityScheme.
+///
+/// Transforms uncovr's security scheme definitions into OpenAPI 3.0 security scheme
+/// specifications for automatic documentation generation.
+fn to_openapi_security_scheme(scheme: &SecurityScheme) -> OpenApiSecurityScheme {
+    match scheme {
         SecurityScheme::Bearer => OpenApiSecurityScheme::Http {
             scheme: "bearer".to_string(),
             bearer_format: Some("JWT".to_string()),
@@ -317,6 +1610,12 @@ fn to_openapi_security_scheme(scheme: &SecurityScheme) -> OpenApiSecurityScheme
                 extensions: Default::default(),
             }
         }
+        SecurityScheme::Session { cookie } => OpenApiSecurityScheme::ApiKey {
+            location: OpenApiKeyLocation::Cookie,
+            name: cookie.to_string(),
+            description: Some("Session cookie".to_string()),
+            extensions: Default::default(),
+        },
         SecurityScheme::OAuth2 { .. } => {
             // For OAuth2, we create a basic placeholder
             // Users can customize this via response_config if needed
@@ -368,6 +1667,13 @@ impl ServerBuilder {
             self.openapi = Some(openapi_config.build());
         }
 
+        self.problem_details |= config.problem_details;
+
+        #[cfg(feature = "secure-cookies")]
+        if let Some(secret) = &config.cookie_secret {
+            self = self.with_cookie_secret(secret.as_bytes());
+        }
+
         self.config = Some(config);
         self
     }
@@ -379,6 +1685,126 @@ impl ServerBuilder {
         self
     }
 
+    /// Enable content negotiation for JSON responses
+    ///
+    /// Applies the [`ContentNegotiation`] middleware to every route, re-encoding JSON
+    /// responses (including errors) in the format preferred by the `Accept` header,
+    /// and lists every enabled [`ResponseFormat`] in the OpenAPI responses. Routes that
+    /// do not return JSON and the documentation routes are not affected.
+    ///
+    /// MessagePack, CBOR and YAML are enabled with the `msgpack`, `cbor` and `yaml`
+    /// cargo features.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use uncovr::server::Server;
+    ///
+    /// let server = Server::new()
+    ///     .with_content_negotiation()
+    ///     .build();
+    /// ```
+    pub fn with_content_negotiation(mut self) -> Self {
+        self.content_negotiation = true;
+        self
+    }
+
+    /// Render errors as RFC 9457 problem details
+    ///
+    /// Handler errors ([`Error`] and any [`ApiError`](crate::api::response::ApiError))
+    /// and framework-generated errors are returned as [`ProblemDetails`] with
+    /// `Content-Type: application/problem+json`. The error code becomes the `code`
+    /// extension member and object `details` are merged into the extension members.
+    /// When the [`RequestId`](crate::middleware::RequestId) middleware wraps the
+    /// endpoints (added with [`layer()`](Self::layer) after registering them), its ID
+    /// fills the `instance` member.
+    ///
+    /// Error responses in the OpenAPI specification are documented with the
+    /// `ProblemDetails` schema. Also enabled with [`App::with_problem_details()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use uncovr::server::Server;
+    /// use uncovr::middleware::RequestId;
+    ///
+    /// let server = Server::new()
+    ///     .with_problem_details()
+    ///     .layer(RequestId::new())
+    ///     .build();
+    /// ```
+    pub fn with_problem_details(mut self) -> Self {
+        self.problem_details = true;
+        self
+    }
+
+    /// Set the key for signed and private cookies
+    ///
+    /// Handlers sign and encrypt cookies with `ctx.cookies().sign()` and
+    /// `ctx.cookies().encrypt()`, and read them back with `get_signed()` and
+    /// `get_private()`. Requires the `secure-cookies` cargo feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use uncovr::server::Server;
+    /// use uncovr::server::cookies::Key;
+    ///
+    /// let server = Server::new()
+    ///     .with_cookie_key(Key::generate())
+    ///     .build();
+    /// ```
+    #[cfg(feature = "secure-cookies")]
+    pub fn with_cookie_key(mut self, key: crate::server::cookies::Key) -> Self {
+        self.cookie_key = Some(key);
+        self
+    }
+
+    /// Derive the key for signed and private cookies from a secret
+    ///
+    /// Also set with [`App::with_cookie_secret()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the secret is shorter than 32 bytes.
+    #[cfg(feature = "secure-cookies")]
+    pub fn with_cookie_secret(self, secret: &[u8]) -> Self {
+        assert!(
+            secret.len() >= 32,
+            "Cookie secret must be at least 32 bytes, got {}",
+            secret.len()
+        );
+        self.with_cookie_key(crate::server::cookies::Key::derive_from(secret))
+    }
+
+    /// Customize errors generated by the framework
+    ///
+    /// uncovr renders its own failures (unknown routes, unsupported methods, malformed
+    /// bodies and parameters, size limits and unsupported media types) as [`Error`]
+    /// values. The hook receives each of them and returns the error to send instead.
+    /// Errors returned by handlers are not passed to the hook.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use uncovr::prelude::*;
+    /// use uncovr::server::Server;
+    ///
+    /// let server = Server::new()
+    ///     .map_rejection(|error| match error {
+    ///         Error::NotFound { .. } => Error::not_found("not_found", "Nothing here"),
+    ///         error => error,
+    ///     })
+    ///     .build();
+    /// ```
+    pub fn map_rejection<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(Error) -> Error + Send + Sync + 'static,
+    {
+        self.rejection_hook = Some(Arc::new(hook));
+        self
+    }
+
     /// Configure logging for the server
     ///
     /// This is now separate from App for better modularity.
@@ -449,10 +1875,24 @@ impl ServerBuilder {
     /// # Generic Bounds
     ///
     /// The endpoint must satisfy:
-    /// - `E::Request`: Deserializable from JSON and has a default (for GET requests without body)
+    /// - `E::Request`: Deserializable from every [`BodyEncoding`] the route accepts (JSON by
+    ///   default) and documented with a JSON schema. Routes whose [`BodyPolicy`] is `None` or
+    ///   `Optional` also need it to deserialize from JSON `null` or `{}` when no body is sent
+    ///   (see [`Handler::Request`]), or to be registered with
+    ///   [`register_default()`](Self::register_default). Otherwise requests without a body
+    ///   are rejected with a 400 `missing_body` error
     /// - `E::Response`: Convertible to HTTP response and has OpenAPI schema information
     /// - All types are `Send + 'static` for async runtime compatibility
     ///
+    /// # Panics
+    ///
+    /// Panics if the route declares path parameters that do not exactly match the
+    /// `:name` and `*wildcard` captures of its path. Routes without declared path
+    /// parameters have them inferred from the path instead.
+    ///
+    /// Also panics if the route's [`BodyPolicy`] is `None` and its request type cannot be
+    /// built from JSON `null` or `{}`, since no request to it could succeed.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -495,408 +1935,380 @@ impl ServerBuilder {
     ///     .await
     ///     .unwrap();
     /// ```
-    pub fn register<E>(mut self, endpoint: E) -> Self
+    pub fn register<E>(self, endpoint: E) -> Self
+    where
+        E: EndpointTrait + Handler + Send + Sync + 'static,
+        E::Request: serde::de::DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        E::Response: aide::OperationOutput + axum::response::IntoResponse + Send + 'static,
+    {
+        self.register_endpoint(endpoint, empty_request, |_| Ok(()))
+    }
+
+    /// Register an endpoint whose request value defaults when no body is sent.
+    ///
+    /// Works like [`register()`](Self::register), but requests without a body receive
+    /// `E::Request::default()`. Use it for routes that read no body, or an optional one,
+    /// with request types that implement [`Default`] but cannot be deserialized from `{}`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use uncovr::prelude::*;
+    ///
+    /// #[derive(Default, Deserialize, JsonSchema)]
+    /// struct Greeting {
+    ///     name: String,
+    /// }
+    ///
+    /// // `GET /hello` reads no body, so the handler receives `Greeting::default()`
+    /// Server::new()
+    ///     .register_default(Hello)
+    ///     .serve()
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    pub fn register_default<E>(self, endpoint: E) -> Self
     where
         E: EndpointTrait + Handler + Send + Sync + 'static,
         E::Request: serde::de::DeserializeOwned + schemars::JsonSchema + Default + Send + 'static,
         E::Response: aide::OperationOutput + axum::response::IntoResponse + Send + 'static,
-        <E::Response as aide::OperationOutput>::Inner: schemars::JsonSchema,
+    {
+        self.register_endpoint(endpoint, default_request, |_| Ok(()))
+    }
+
+    /// Register an endpoint whose request is validated before the handler runs.
+    ///
+    /// Works like [`register()`](Self::register), then calls `Validate::validate` on the
+    /// decoded request. Failures are rejected with a 422 `validation_failed` error whose
+    /// details map field paths (such as `address.city` or `items[0].name`) to messages,
+    /// and are passed to the [`map_rejection()`](Self::map_rejection) hook like other
+    /// framework errors.
+    ///
+    /// `#[validate(...)]` length, range, email and url constraints are reflected in the
+    /// request schema by `#[derive(JsonSchema)]`. Regular expressions are referenced by
+    /// path, so document their pattern with `#[schemars(regex(pattern = "..."))]`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use uncovr::prelude::*;
+    ///
+    /// #[derive(Deserialize, JsonSchema, Validate)]
+    /// struct CreateUserRequest {
+    ///     #[validate(length(min = 1, max = 50))]
+    ///     name: String,
+    ///     #[validate(email)]
+    ///     email: String,
+    /// }
+    ///
+    /// // No `ctx.req.validate()?` needed in `CreateUser::handle()`
+    /// Server::new()
+    ///     .register_validated(CreateUser)
+    ///     .serve()
+    ///     .await
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "validation")]
+    pub fn register_validated<E>(self, endpoint: E) -> Self
+    where
+        E: EndpointTrait + Handler + Send + Sync + 'static,
+        E::Request: serde::de::DeserializeOwned
+            + schemars::JsonSchema
+            + validator::Validate
+            + Send
+            + 'static,
+        E::Response: aide::OperationOutput + axum::response::IntoResponse + Send + 'static,
+    {
+        self.register_endpoint(endpoint, empty_request, |req| {
+            Ok(validator::Validate::validate(req)?)
+        })
+    }
+
+    /// Registers an endpoint, building the request value with `empty` when no body
+    /// is sent and checking each decoded request with `validate`.
+    fn register_endpoint<E>(
+        mut self,
+        endpoint: E,
+        empty: fn() -> Option<E::Request>,
+        validate: fn(&E::Request) -> Result<(), Error>,
+    ) -> Self
+    where
+        E: EndpointTrait + Handler + Send + Sync + 'static,
+        E::Request: serde::de::DeserializeOwned + schemars::JsonSchema + Send + 'static,
+        E::Response: aide::OperationOutput + axum::response::IntoResponse + Send + 'static,
     {
         let route_def = endpoint.route();
         let meta = endpoint.meta();
 
         let path = route_def.path;
-        let method = route_def.method.as_str();
-        let summary = meta.summary.unwrap_or("");
-        let description = meta.description;
-        let tags = meta.tags.clone();
-        let response_config = meta.response_config;
-        let security_schemes = meta.security.clone();
-
-        let endpoint = Arc::new(endpoint);
-
-        let route = match method {
-            "get" => {
-                let ep = Arc::clone(&endpoint);
-                get_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions| {
-                        let ep = Arc::clone(&ep);
-                        async move {
-                            let ctx = Context {
-                                req: E::Request::default(),
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        // Add query parameters
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
-
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
-
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
-
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
-                            }
-                        }
-
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
-
-                        op
-                    },
-                )
+        let body = BodyConfig {
+            policy: route_def.body,
+            encodings: route_def.encodings.clone().into(),
+            max_file_size: route_def.max_file_size,
+        };
+        if body.policy != BodyPolicy::Required && empty().is_none() {
+            let route = format!("{} {}", route_def.method.as_str().to_uppercase(), path);
+            // A route that never reads a body could not answer any request
+            if body.policy == BodyPolicy::None {
+                panic!(
+                    "Route `{}` reads no body, but its request type cannot be built from an \
+                     empty body. Add `#[serde(default)]` to the type or register it with \
+                     `register_default()`.",
+                    route,
+                );
             }
-            "post" => {
-                let ep = Arc::clone(&endpoint);
-                post_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions,
-                          axum::Json(payload): axum::Json<E::Request>| {
-                        let ep = Arc::clone(&ep);
-                        async move {
-                            let ctx = Context {
-                                req: payload,
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
+            tracing::warn!(
+                "Route `{}` accepts requests without a body, but its request type cannot be \
+                 built from an empty body; such requests will be rejected with `missing_body`. \
+                 Add `#[serde(default)]` to the type, register it with `register_default()` or \
+                 use `BodyPolicy::Required`.",
+                route,
+            );
+        }
+        let path_params = resolve_path_params(&route_def).unwrap_or_else(|err| panic!("{}", err));
+        let query_params: Vec<ParamInfo> = route_def
+            .query_params
+            .iter()
+            .map(|param| ParamInfo {
+                name: param.name,
+                description: param.description,
+                required: param.required,
+                schema: param.schema.clone(),
+                style: param.resolved_style(),
+            })
+            .collect();
+        let header_params: Vec<ParamInfo> = route_def
+            .header_params
+            .iter()
+            .map(|param| ParamInfo {
+                name: param.name,
+                description: param.description,
+                required: param.required,
+                schema: param.schema.clone(),
+                style: ParamStyle::Form,
+            })
+            .collect();
+        let cookie_params: Vec<ParamInfo> = route_def
+            .cookie_params
+            .iter()
+            .map(|param| ParamInfo {
+                name: param.name,
+                description: param.description,
+                required: param.required,
+                schema: param.schema.clone(),
+                style: ParamStyle::Form,
+            })
+            .collect();
 
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
+        if let Some(scheme) = meta
+            .security
+            .iter()
+            .find(|scheme| matches!(scheme, SecurityScheme::Session { .. }))
+        {
+            self.session_scheme = Some(scheme.clone());
+        }
 
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
+        let layers = endpoint.layers();
+        let endpoint = Arc::new(endpoint);
+        let json_responses = documents_json::<E::Response>();
+        let pagination = route_def.pagination;
+        let filter = route_def.filter.map(Arc::new);
 
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
-                            }
-                        }
+        let checks = path_params
+            .iter()
+            .filter_map(|param| ParamCheck::new(param, ParamLocation::Path))
+            .chain(
+                query_params
+                    .iter()
+                    // Pagination parameters are parsed and clamped by `Paginate::extract()`
+                    .filter(|param| !pagination.is_some_and(|paginate| paginate.reads(param.name)))
+                    .filter_map(|param| ParamCheck::new(param, ParamLocation::Query)),
+            )
+            .chain(
+                header_params
+                    .iter()
+                    .filter_map(|param| ParamCheck::new(param, ParamLocation::Header)),
+            )
+            .chain(
+                cookie_params
+                    .iter()
+                    .filter_map(|param| ParamCheck::new(param, ParamLocation::Cookie)),
+            )
+            .collect();
+        let params = RouteParams {
+            typed_query: route_def.typed_query,
+            typed_path: route_def.typed_path,
+            checks,
+        };
 
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
+        let docs = OperationDocs {
+            params: params.clone(),
+            filter: filter.clone(),
+            path_params,
+            query_params,
+            header_params,
+            cookie_params,
+            summary: meta.summary.unwrap_or(""),
+            description: meta.description,
+            tags: meta.tags,
+            security: meta.security,
+            body: body.clone(),
+            document_body: document_body::<E::Request>,
+            errors: meta.errors,
+            success_status: meta.success_status,
+            response_headers: meta.response_headers,
+            response_config: meta.response_config,
+        };
 
-                        op
-                    },
-                )
+        let handler = move |path_params: Result<
+            axum::extract::Path<HashMap<String, String>>,
+            PathRejection,
+        >,
+                            parts: ExtractRequestParts,
+                            RawRequest(request): RawRequest| {
+            let ep = Arc::clone(&endpoint);
+            let params = params.clone();
+            let filter = filter.clone();
+            let body = body.clone();
+            async move {
+                if json_responses && request.extensions().get::<NotAcceptable>().is_some() {
+                    return Err(not_acceptable_error().into());
+                }
+                let axum::extract::Path(path_params) = path_params.map_err(|rejection| {
+                    Error::bad_request("invalid_path", rejection.body_text())
+                })?;
+                let (path, query) = params.extract(path_params, &parts.uri, &parts.headers)?;
+                let list_query = filter
+                    .map(|filter| filter.parse(parts.uri.query()))
+                    .transpose()?;
+                let page = pagination
+                    .map(|paginate| paginate.extract(&query, &parts.original_uri))
+                    .transpose()?;
+                let (payload, uploads) = body.read(request, empty).await?;
+                validate(&payload)?;
+                let mut ctx = parts.into_context(payload, path, query);
+                if !uploads.is_empty() {
+                    ctx.extensions.insert(uploads);
+                }
+                if let Some(page) = page {
+                    ctx.extensions.insert(page);
+                }
+                if let Some(list_query) = list_query {
+                    ctx.extensions.insert(list_query);
+                }
+                Ok::<_, Rejection>(ep.handle(ctx).await)
             }
-            "put" => {
-                let ep = Arc::clone(&endpoint);
-                put_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions,
-                          axum::Json(payload): axum::Json<E::Request>| {
-                        let ep = Arc::clone(&ep);
-                        async move {
-                            let ctx = Context {
-                                req: payload,
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
-
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
+        };
 
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
+        self.allowed_methods
+            .write()
+            .unwrap()
+            .entry(path)
+            .or_default()
+            .push(route_def.method.to_method());
 
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
-                            }
-                        }
+        self.add_route(
+            path,
+            route_def.method,
+            route_def.max_body_size,
+            handler,
+            docs,
+            layers,
+        );
+        self
+    }
 
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
+    /// Routes a handler for a single method on a path.
+    fn add_route<H, I, O, T>(
+        &mut self,
+        path: &'static str,
+        method: HttpMethod,
+        max_body_size: Option<usize>,
+        handler: H,
+        docs: OperationDocs,
+        layers: Layers,
+    ) where
+        H: axum::handler::Handler<T, ()> + aide::operation::OperationHandler<I, O>,
+        I: aide::OperationInput,
+        O: aide::OperationOutput,
+        T: 'static,
+    {
+        // Declared errors are checked against handler responses in debug builds
+        let undeclared_check =
+            docs.errors
+                .as_ref()
+                .filter(|_| cfg!(debug_assertions))
+                .map(|errors| {
+                    let declared: Arc<[StatusCode]> =
+                        errors.iter().map(|error| error.status).collect();
+                    let route = format!("{} {}", method.as_str().to_uppercase(), path);
+                    (declared, Arc::<str>::from(route))
+                });
 
-                        op
-                    },
-                )
-            }
-            "delete" => {
-                let ep = Arc::clone(&endpoint);
-                delete_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions,
-                          axum::Json(payload): axum::Json<E::Request>| {
-                        let ep = Arc::clone(&ep);
+        let route = match method {
+            HttpMethod::GET => get_with(handler, |op| docs.apply(op)),
+            HttpMethod::POST => post_with(handler, |op| docs.apply(op)),
+            HttpMethod::PUT => put_with(handler, |op| docs.apply(op)),
+            HttpMethod::PATCH => patch_with(handler, |op| docs.apply(op)),
+            HttpMethod::DELETE => delete_with(handler, |op| docs.apply(op)),
+            HttpMethod::HEAD => head_with(handler, |op| docs.apply(op)),
+            HttpMethod::TRACE => trace_with(handler, |op| docs.apply(op)),
+            HttpMethod::OPTIONS => {
+                let allowed_methods = Arc::clone(&self.allowed_methods);
+                options_with(handler, |op| docs.apply(op)).layer(axum::middleware::map_response(
+                    move |mut response: Response<Body>| {
+                        let allow = allow_header(&allowed_methods, path);
                         async move {
-                            let ctx = Context {
-                                req: payload,
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
-
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
-
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
-
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
+                            if let Some(allow) = allow {
+                                response.headers_mut().entry(ALLOW).or_insert(allow);
                             }
+                            response
                         }
-
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
-
-                        op
                     },
-                )
+                ))
             }
-            "patch" => {
-                let ep = Arc::clone(&endpoint);
-                patch_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions,
-                          axum::Json(payload): axum::Json<E::Request>| {
-                        let ep = Arc::clone(&ep);
-                        async move {
-                            let ctx = Context {
-                                req: payload,
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
-
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
-
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
-
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
-                            }
-                        }
-
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
-
-                        op
-                    },
-                )
+            HttpMethod::Other(method) => {
+                // Extension methods cannot be expressed with axum's method filters or in
+                // the OpenAPI document, so they are dispatched by a per-path fallback
+                // assembled in `build()`.
+                let mut route = axum::routing::any(handler);
+                if let Some(limit) = max_body_size {
+                    route = route.layer(DefaultBodyLimit::max(limit));
+                }
+                if let Some((declared, route_name)) = undeclared_check {
+                    route = route.route_layer(axum::middleware::map_response(
+                        move |response: Response<Body>| {
+                            warn_undeclared_error(&response, &declared, &route_name);
+                            async move { response }
+                        },
+                    ));
+                }
+                self.extension_routes
+                    .push((path, method, layers.apply_plain(route)));
+                return;
             }
-            _ => {
-                let ep = Arc::clone(&endpoint);
-                get_with(
-                    move |axum::extract::Path(path_params): axum::extract::Path<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          axum::extract::Query(query_params): axum::extract::Query<
-                        std::collections::HashMap<String, String>,
-                    >,
-                          ExtractExtensions(ext): ExtractExtensions| {
-                        let ep = Arc::clone(&ep);
-                        async move {
-                            let ctx = Context {
-                                req: E::Request::default(),
-                                headers: Default::default(),
-                                path: Path::new(path_params),
-                                query: Query::new(query_params),
-                                extensions: ext,
-                            };
-                            ep.handle(ctx).await
-                        }
-                    },
-                    |mut op| {
-                        for param in &route_def.query_params {
-                            let param_info = ParamInfo {
-                                name: param.name,
-                                description: param.description,
-                                required: param.required,
-                            };
-                            op.inner_mut()
-                                .parameters
-                                .push(param_info_to_query_param(&param_info));
-                        }
-
-                        op = op.summary(summary);
-                        if let Some(desc) = description {
-                            op = op.description(desc);
-                        }
-
-                        for tag in &tags {
-                            op = op.tag(tag);
-                        }
-
-                        // Add security requirements
-                        if !security_schemes.is_empty() {
-                            for scheme in &security_schemes {
-                                let mut security_req = SecurityRequirement::new();
-                                security_req
-                                    .insert(security_scheme_name(scheme).to_string(), vec![]);
-                                op.inner_mut().security.push(security_req);
-                            }
-                        }
+        };
 
-                        // Apply response config callback if provided
-                        if let Some(callback) = response_config {
-                            op = callback(op);
-                        }
+        let route = match max_body_size {
+            Some(limit) => route.layer(DefaultBodyLimit::max(limit)),
+            None => route,
+        };
 
-                        op
-                    },
-                )
-            }
+        let route = match undeclared_check {
+            Some((declared, route_name)) => route.route_layer(axum::middleware::map_response(
+                move |response: Response<Body>| {
+                    warn_undeclared_error(&response, &declared, &route_name);
+                    async move { response }
+                },
+            )),
+            None => route,
         };
 
-        self.router = self.router.api_route(path, route);
-        self
+        // Endpoint layers wrap the route, inside any server layers added afterwards
+        let route = layers.apply(route);
+
+        let router = std::mem::take(&mut self.router);
+        self.router = router.api_route(path, route);
     }
 
     /// Merge another router into this server builder
@@ -949,6 +2361,8 @@ impl ServerBuilder {
 
     /// Build the server with the configured options
     pub fn build(mut self) -> Server {
+        self.route_extension_methods();
+
         // Initialize logging if configured
         if let Some(ref logging) = self.logging {
             crate::logging::init(logging);
@@ -997,9 +2411,16 @@ impl ServerBuilder {
             };
 
             // Add documentation routes
+            // Documentation is served as-is, whatever the negotiated format
             let docs_router = ApiRouter::new()
                 .route(openapi_json_path, get_with(serve_docs, |op| op))
-                .route(docs_path, get_with(ui_handler, |op| op));
+                .route(docs_path, get_with(ui_handler, |op| op))
+                .layer(axum::middleware::map_response(
+                    |mut response: Response<Body>| async move {
+                        response.extensions_mut().insert(Unnegotiated);
+                        response
+                    },
+                ));
 
             // Generate and set up the OpenAPI documentation
             let mut api = api.clone();
@@ -1033,19 +2454,67 @@ impl ServerBuilder {
                     })),
                 );
 
-            let router = self.router.finish_api(&mut api);
+            // Session cookies are only documented when an endpoint requires them
+            if let Some(scheme) = &self.session_scheme {
+                api.components
+                    .get_or_insert_with(Default::default)
+                    .security_schemes
+                    .insert(
+                        "sessionAuth".to_string(),
+                        ReferenceOr::Item(to_openapi_security_scheme(scheme)),
+                    );
+            }
 
-            self.router = docs_router.merge(router).layer(Extension(api));
+            let router = self.router.finish_api(&mut api);
 
-            // Apply trace layer if enabled
-            if let Some(trace) = trace_layer {
-                self.router = self.router.layer(trace);
+            if self.problem_details {
+                use_problem_details(&mut api);
             }
-        } else {
-            // Apply trace layer if enabled
-            if let Some(trace) = trace_layer {
-                self.router = self.router.layer(trace);
+
+            if self.content_negotiation {
+                add_response_formats(&mut api);
             }
+
+            self.router = docs_router.merge(router).layer(Extension(api));
+        }
+
+        // Unknown routes and methods are rendered as `Error` bodies
+        let not_found = !self.has_fallback;
+        self.router = self.router.layer(axum::middleware::from_fn(
+            move |request: Request<Body>, next: axum::middleware::Next| {
+                routing_error(request, next, not_found)
+            },
+        ));
+
+        #[cfg(feature = "secure-cookies")]
+        if let Some(key) = self.cookie_key.take() {
+            let key = crate::server::cookies::CookieKey(key);
+            self.router = self.router.layer(Extension(key));
+        }
+
+        if let Some(hook) = self.rejection_hook {
+            self.router = self.router.layer(axum::middleware::map_response(
+                move |response: Response<Body>| {
+                    let response = apply_rejection_hook(&hook, response);
+                    async move { response }
+                },
+            ));
+        }
+
+        if self.problem_details {
+            self.router = self.router.layer(axum::middleware::map_response(
+                |response: Response<Body>| async move { into_problem_details(response) },
+            ));
+        }
+
+        if self.content_negotiation {
+            let negotiation = ContentNegotiation::new().problem_details(self.problem_details);
+            self.router = self.router.layer(negotiation);
+        }
+
+        // Apply trace layer if enabled
+        if let Some(trace) = trace_layer {
+            self.router = self.router.layer(trace);
         }
 
         let address = self.address.parse().expect("Invalid bind address");
@@ -1055,6 +2524,55 @@ impl ServerBuilder {
         }
     }
 
+    /// Installs a dispatcher for every path with extension-method endpoints.
+    ///
+    /// The dispatcher is registered as the path's method fallback, so it only sees
+    /// requests whose method has no standard route. Unknown methods receive a
+    /// 405 response with the `Allow` header.
+    fn route_extension_methods(&mut self) {
+        let mut by_path: HashMap<&'static str, Vec<(Method, axum::routing::MethodRouter)>> =
+            HashMap::new();
+        for (path, method, route) in self.extension_routes.drain(..) {
+            by_path.entry(path).or_default().push((method, route));
+        }
+
+        for (path, routes) in by_path {
+            let routes = Arc::new(routes);
+            let allowed_methods = Arc::clone(&self.allowed_methods);
+
+            let dispatcher = tower::service_fn(move |request: Request<Body>| {
+                let routes = Arc::clone(&routes);
+                let allow = allow_header(&allowed_methods, path);
+                async move {
+                    let route = routes
+                        .iter()
+                        .find(|(method, _)| method == request.method())
+                        .map(|(_, route)| route.clone());
+
+                    match route {
+                        Some(route) => route.oneshot(request).await,
+                        None => {
+                            let mut response =
+                                method_not_allowed(request.method().clone(), request.uri().clone())
+                                    .await
+                                    .into_response();
+                            if let Some(allow) = allow {
+                                response.headers_mut().insert(ALLOW, allow);
+                            }
+                            Ok(response)
+                        }
+                    }
+                }
+            });
+
+            let router = std::mem::take(&mut self.router);
+            self.router = router.route(
+                path,
+                axum::routing::MethodRouter::new().fallback_service(dispatcher),
+            );
+        }
+    }
+
     /// Build and start serving the application.
     ///
     /// This is a convenience method that combines `build()` and `serve()` into a single call.
@@ -1090,6 +2608,9 @@ impl ServerBuilder {
     /// This allows adding any Tower-compatible layer, such as logging, compression,
     /// rate limiting, authentication, etc.
     ///
+    /// The layer wraps the endpoints registered before it. To apply a layer to a single
+    /// endpoint, return it from [`Endpoint::layers()`](crate::server::Endpoint::layers).
+    ///
     /// # Example
     /// ```
     /// use tower_http::compression::CompressionLayer;
@@ -1138,6 +2659,7 @@ impl ServerBuilder {
         T: 'static,
     {
         self.router = self.router.fallback(handler);
+        self.has_fallback = true;
         self
     }
 
@@ -1179,6 +2701,7 @@ impl ServerBuilder {
         S::Future: Send + 'static,
     {
         self.router = self.router.fallback_service(service);
+        self.has_fallback = true;
         self
     }
 
@@ -1251,12 +2774,35 @@ mod tests {
         }
     }
 
+    /// Sends a request through a built server and returns the status and body.
+    async fn send(server: Server, request: Request<Body>) -> (http::StatusCode, Vec<u8>) {
+        use tower::ServiceExt;
+
+        let router: axum::Router = server.into_router().into();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, body.to_vec())
+    }
+
+    /// Builds a server with OpenAPI enabled and returns the generated specification.
+    async fn openapi_spec(builder: ServerBuilder) -> serde_json::Value {
+        let server = builder
+            .with_openapi(OpenApiConfig::new("Spec API", "1.0.0"))
+            .build();
+        let request = Request::get("/openapi.json").body(Body::empty()).unwrap();
+        let (_, body) = send(server, request).await;
+        serde_json::from_slice(&body).unwrap()
+    }
+
     #[tokio::test]
     async fn test_server_builder() {
         let server = Server::new()
             .with_openapi(OpenApiConfig::new("Test API", "1.0.0"))
             .bind("127.0.0.1:3001")
-            .register(TestEndpoint)
+            .register_default(TestEndpoint)
             .build();
 
         assert_eq!(server.address.to_string(), "127.0.0.1:3001");
@@ -1265,7 +2811,10 @@ mod tests {
     #[tokio::test]
     async fn test_nested_routes() {
         // Create v1 routes
-        let v1_routes = Server::new().register(TestEndpoint).build().into_router();
+        let v1_routes = Server::new()
+            .register_default(TestEndpoint)
+            .build()
+            .into_router();
 
         // Create v2 routes
         #[derive(Clone)]
@@ -1298,7 +2847,10 @@ mod tests {
             }
         }
 
-        let v2_routes = Server::new().register(V2TestEndpoint).build().into_router();
+        let v2_routes = Server::new()
+            .register_default(V2TestEndpoint)
+            .build()
+            .into_router();
 
         // Nest both under versioned paths
         let server = Server::new()
@@ -1389,10 +2941,2464 @@ mod tests {
         let server = Server::new()
             .with_openapi(OpenApiConfig::new("Service Nesting Test", "1.0.0"))
             .bind("127.0.0.1:3004")
-            .register(TestEndpoint)
+            .register_default(TestEndpoint)
             .nest_service("/external", external_service)
             .build();
 
         assert_eq!(server.address.to_string(), "127.0.0.1:3004");
     }
+
+    #[tokio::test]
+    async fn test_context_receives_request_head() {
+        #[derive(Clone)]
+        struct EchoHead;
+
+        impl Endpoint for EchoHead {
+            fn route(&self) -> Route {
+                Route::get("/echo/:id")
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Handler for EchoHead {
+            type Request = ();
+            type Response = String;
+
+            async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+                format!(
+                    "{} {} {} {}",
+                    ctx.method,
+                    ctx.uri,
+                    ctx.matched_path.unwrap_or_default(),
+                    ctx.headers
+                        .get("x-tenant-id")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or("none")
+                )
+            }
+        }
+
+        let request = Request::builder()
+            .uri("/echo/7?lang=sw")
+            .header("x-tenant-id", "acme")
+            .body(Body::empty())
+            .unwrap();
+        let (_, body) = send(Server::new().register(EchoHead).build(), request).await;
+
+        assert_eq!(&body[..], b"GET /echo/7?lang=sw /echo/:id acme");
+    }
+
+    #[derive(Clone)]
+    struct GetFile;
+
+    impl Endpoint for GetFile {
+        fn route(&self) -> Route {
+            let mut route = Route::get("/users/:id/files/*path");
+            route.path_param("id").desc("User ID");
+            route.path_param("path").desc("File path");
+            route
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for GetFile {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.path.get_string("path").unwrap_or_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_path_params_documented() {
+        let spec = openapi_spec(Server::new().register(GetFile)).await;
+        let params = &spec["paths"]["/users/{id}/files/{path+}"]["get"]["parameters"];
+
+        let id = params
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "id")
+            .expect("id parameter should be documented");
+        assert_eq!(id["in"], "path");
+        assert_eq!(id["required"], true);
+        assert_eq!(id["description"], "User ID");
+    }
+
+    #[derive(Clone)]
+    struct ListUsers;
+
+    impl Endpoint for ListUsers {
+        fn route(&self) -> Route {
+            let mut route = Route::get("/users");
+            route
+                .query("limit")
+                .schema::<u32>()
+                .default(20)
+                .example(50)
+                .desc("Page size");
+            route
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for ListUsers {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.query.parse::<u32>("limit").unwrap_or(20).to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_query_param_documented() {
+        let spec = openapi_spec(Server::new().register(ListUsers)).await;
+        let limit = &spec["paths"]["/users"]["get"]["parameters"][0];
+
+        assert_eq!(limit["name"], "limit");
+        assert_eq!(limit["schema"]["type"], "integer");
+        assert_eq!(limit["schema"]["default"], 20);
+        assert_eq!(limit["example"], 50);
+
+        let request = Request::get("/users?limit=5").body(Body::empty()).unwrap();
+        let (_, body) = send(Server::new().register(ListUsers).build(), request).await;
+        assert_eq!(&body[..], b"5");
+    }
+
+    #[derive(Deserialize, schemars::JsonSchema)]
+    struct OrderPath {
+        id: u64,
+    }
+
+    #[derive(Deserialize, schemars::JsonSchema)]
+    struct OrderQuery {
+        /// Include line items
+        expand: Option<bool>,
+    }
+
+    #[derive(Clone)]
+    struct GetOrder;
+
+    impl Endpoint for GetOrder {
+        fn route(&self) -> Route {
+            Route::get("/orders/:id")
+                .path_as::<OrderPath>()
+                .query_as::<OrderQuery>()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for GetOrder {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let id = ctx.path_typed::<OrderPath>().map_or(0, |path| path.id);
+            let expand = ctx
+                .query_typed::<OrderQuery>()
+                .and_then(|query| query.expand)
+                .unwrap_or(false);
+            // Types the route did not declare are not found
+            let undeclared = ctx.query_typed::<OrderPath>().is_none();
+            format!("{} {} {}", id, expand, undeclared)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_typed_params_deserialized() {
+        let request = Request::get("/orders/42?expand=true")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(Server::new().register(GetOrder).build(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"42 true true");
+
+        let request = Request::get("/orders/abc").body(Body::empty()).unwrap();
+        let (status, body) = send(Server::new().register(GetOrder).build(), request).await;
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_path");
+    }
+
+    #[tokio::test]
+    async fn test_typed_params_documented() {
+        let spec = openapi_spec(Server::new().register(GetOrder)).await;
+        let params = spec["paths"]["/orders/{id}"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .clone();
+
+        assert_eq!(params.len(), 2);
+        assert_eq!(params[0]["name"], "id");
+        assert_eq!(params[0]["in"], "path");
+        assert_eq!(params[0]["schema"]["type"], "integer");
+        assert_eq!(params[1]["name"], "expand");
+        assert_eq!(params[1]["in"], "query");
+        assert_ne!(params[1]["required"], true);
+    }
+
+    #[test]
+    fn test_path_params_inferred_from_template() {
+        let params = resolve_path_params(&Route::get("/users/:id/files/*path")).unwrap();
+        let names: Vec<_> = params.iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["id", "path"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "declares path parameter `user_id`")]
+    fn test_undeclared_path_param_panics() {
+        #[derive(Clone)]
+        struct Mismatched;
+
+        impl Endpoint for Mismatched {
+            fn route(&self) -> Route {
+                let mut route = Route::get("/users/:id");
+                route.path_param("user_id").desc("User ID");
+                route
+            }
+        }
+
+        #[async_trait::async_trait]
+        impl Handler for Mismatched {
+            type Request = ();
+            type Response = String;
+
+            async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+                String::new()
+            }
+        }
+
+        let _ = Server::new().register(Mismatched);
+    }
+
+    #[test]
+    fn test_missing_path_param_rejected() {
+        let mut route = Route::get("/users/:id/posts/:post_id");
+        route.path_param("id");
+
+        let err = resolve_path_params(&route).unwrap_err();
+        assert!(err.contains("`post_id` which is not declared"));
+    }
+
+    /// Endpoint answering with the request method on a configurable route.
+    #[derive(Clone)]
+    struct MethodEcho(Route);
+
+    impl Endpoint for MethodEcho {
+        fn route(&self) -> Route {
+            self.0.clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for MethodEcho {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.method.to_string()
+        }
+    }
+
+    fn method_server() -> ServerBuilder {
+        Server::new()
+            .register(MethodEcho(Route::get("/items")))
+            .register(MethodEcho(Route::head("/items")))
+            .register(MethodEcho(Route::options("/items")))
+            .register(MethodEcho(Route::new(
+                Method::from_bytes(b"PROPFIND").unwrap(),
+                "/items",
+            )))
+    }
+
+    #[tokio::test]
+    async fn test_head_and_options_routed() {
+        let request = Request::head("/items").body(Body::empty()).unwrap();
+        let (status, body) = send(method_server().build(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert!(body.is_empty());
+
+        let router: axum::Router = method_server().build().into_router().into();
+        let request = Request::options("/items").body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()[ALLOW], "GET, HEAD, OPTIONS, PROPFIND");
+    }
+
+    #[tokio::test]
+    async fn test_extension_method_routed() {
+        let request = Request::builder()
+            .method("PROPFIND")
+            .uri("/items")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(method_server().build(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"PROPFIND");
+
+        let router: axum::Router = method_server().build().into_router().into();
+        let request = Request::builder()
+            .method("MKCOL")
+            .uri("/items")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::METHOD_NOT_ALLOWED);
+        assert!(response.headers().contains_key(ALLOW));
+    }
+
+    #[tokio::test]
+    async fn test_head_and_options_documented() {
+        let spec = openapi_spec(method_server()).await;
+        let item = &spec["paths"]["/items"];
+        assert!(item["get"].is_object());
+        assert!(item["head"].is_object());
+        assert!(item["options"].is_object());
+        assert!(item.get("propfind").is_none());
+    }
+
+    #[derive(Deserialize, schemars::JsonSchema)]
+    struct Rename {
+        name: String,
+    }
+
+    /// Endpoint echoing the request body on a configurable route.
+    #[derive(Clone)]
+    struct RenameUser(Route);
+
+    impl Endpoint for RenameUser {
+        fn route(&self) -> Route {
+            self.0.clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for RenameUser {
+        type Request = Option<Rename>;
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.req.map(|rename| rename.name).unwrap_or_default()
+        }
+    }
+
+    /// Endpoint whose request type has no empty value.
+    #[derive(Clone)]
+    struct CreateRename;
+
+    impl Endpoint for CreateRename {
+        fn route(&self) -> Route {
+            Route::post("/renames")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for CreateRename {
+        type Request = Rename;
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.req.name
+        }
+    }
+
+    /// Misconfigured endpoint: a `GET` route whose request type needs a body.
+    #[derive(Clone)]
+    struct LookupRename;
+
+    impl Endpoint for LookupRename {
+        fn route(&self) -> Route {
+            Route::get("/renames")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for LookupRename {
+        type Request = Rename;
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.req.name
+        }
+    }
+
+    fn json_request(method: &str, uri: &str, body: &'static str) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_optional_body() {
+        let server = || {
+            Server::new()
+                .register(RenameUser(Route::delete("/users/:id")))
+                .build()
+        };
+
+        let request = Request::delete("/users/1").body(Body::empty()).unwrap();
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert!(body.is_empty());
+
+        let request = json_request("DELETE", "/users/1", r#"{"name":"ada"}"#);
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada");
+    }
+
+    #[tokio::test]
+    async fn test_body_ignored_without_policy() {
+        let server = Server::new()
+            .register(RenameUser(Route::get("/users/:id")))
+            .build();
+        let request = json_request("GET", "/users/1", r#"{"name":"ada"}"#);
+        let (status, body) = send(server, request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Route `GET /renames` reads no body")]
+    fn test_bodyless_route_with_unbuildable_request_panics() {
+        let _ = Server::new().register(LookupRename);
+    }
+
+    #[tokio::test]
+    async fn test_bodyless_route_with_default_request() {
+        let request = Request::get("/tests").body(Body::empty()).unwrap();
+        let server = Server::new().register_default(TestEndpoint).build();
+        let (status, body) = send(server, request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"Hello, !");
+    }
+
+    #[tokio::test]
+    async fn test_required_body_missing() {
+        let request = Request::post("/renames").body(Body::empty()).unwrap();
+        let (status, body) = send(Server::new().register(CreateRename).build(), request).await;
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "missing_body");
+
+        let request = json_request("POST", "/renames", r#"{"name":"ada"}"#);
+        let (status, body) = send(Server::new().register(CreateRename).build(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada");
+    }
+
+    #[tokio::test]
+    async fn test_body_policy_documented() {
+        let spec = openapi_spec(
+            Server::new()
+                .register(RenameUser(Route::delete("/users/:id")))
+                .register(RenameUser(Route::get("/users/:id")))
+                .register(CreateRename),
+        )
+        .await;
+
+        let user = &spec["paths"]["/users/{id}"];
+        assert!(user["delete"]["requestBody"].is_object());
+        assert_ne!(user["delete"]["requestBody"]["required"], true);
+        assert!(user["get"].get("requestBody").is_none());
+        assert_eq!(
+            spec["paths"]["/renames"]["post"]["requestBody"]["required"],
+            true
+        );
+    }
+
+    #[derive(Deserialize, schemars::JsonSchema)]
+    struct Profile {
+        name: String,
+        age: u32,
+    }
+
+    /// Endpoint describing the profile and uploaded files on a configurable route.
+    #[derive(Clone)]
+    struct UpdateProfile(Route);
+
+    impl Endpoint for UpdateProfile {
+        fn route(&self) -> Route {
+            self.0.clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for UpdateProfile {
+        type Request = Profile;
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let files: Vec<String> = ctx
+                .uploads()
+                .iter()
+                .map(|upload| format!("{}={}", upload.name(), upload.len()))
+                .collect();
+            format!("{} {} [{}]", ctx.req.name, ctx.req.age, files.join(","))
+        }
+    }
+
+    fn multipart_request(parts: &[(&str, Option<&str>, &str)]) -> Request<Body> {
+        let mut body = String::new();
+        for (name, file_name, value) in parts {
+            body.push_str("--BOUNDARY\r\n");
+            match file_name {
+                Some(file_name) => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                     Content-Type: application/octet-stream\r\n\r\n",
+                    name, file_name
+                )),
+                None => body.push_str(&format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n\r\n",
+                    name
+                )),
+            }
+            body.push_str(value);
+            body.push_str("\r\n");
+        }
+        body.push_str("--BOUNDARY--\r\n");
+
+        Request::post("/profile")
+            .header(CONTENT_TYPE, "multipart/form-data; boundary=BOUNDARY")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_form_body() {
+        let server = || {
+            Server::new()
+                .register(UpdateProfile(
+                    Route::post("/profile").encoding(BodyEncoding::Form),
+                ))
+                .build()
+        };
+
+        let request = Request::post("/profile")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("name=ada&age=36"))
+            .unwrap();
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada 36 []");
+
+        let request = json_request("POST", "/profile", r#"{"name":"ada","age":36}"#);
+        let (status, body) = send(server(), request).await;
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(error["code"], "unsupported_media_type");
+    }
+
+    #[tokio::test]
+    async fn test_multipart_body() {
+        let server = || {
+            Server::new()
+                .register(UpdateProfile(
+                    Route::post("/profile")
+                        .encoding(BodyEncoding::Multipart)
+                        .max_file_size(8),
+                ))
+                .build()
+        };
+
+        let request = multipart_request(&[
+            ("name", None, "ada"),
+            ("age", None, "36"),
+            ("avatar", Some("ada.png"), "pixels"),
+        ]);
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada 36 [avatar=6]");
+
+        let request = multipart_request(&[
+            ("name", None, "ada"),
+            ("age", None, "36"),
+            ("avatar", Some("ada.png"), "far too many pixels"),
+        ]);
+        let (status, body) = send(server(), request).await;
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, http::StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error["code"], "file_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_body_encoding_documented() {
+        let spec = openapi_spec(
+            Server::new()
+                .register(UpdateProfile(
+                    Route::post("/profile").encoding(BodyEncoding::Form),
+                ))
+                .register(UpdateProfile(
+                    Route::put("/profile").encoding(BodyEncoding::Multipart),
+                )),
+        )
+        .await;
+
+        let profile = &spec["paths"]["/profile"];
+        let form = &profile["post"]["requestBody"]["content"];
+        assert!(form["application/x-www-form-urlencoded"]["schema"].is_object());
+        assert!(form.get("application/json").is_none());
+
+        let multipart = &profile["put"]["requestBody"]["content"];
+        assert!(multipart["multipart/form-data"]["schema"].is_object());
+        assert_eq!(multipart.as_object().unwrap().len(), 1);
+    }
+
+    #[derive(Serialize, Deserialize, schemars::JsonSchema)]
+    struct Status {
+        healthy: bool,
+    }
+
+    #[derive(Clone)]
+    struct GetStatus;
+
+    impl Endpoint for GetStatus {
+        fn route(&self) -> Route {
+            Route::get("/status")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for GetStatus {
+        type Request = ();
+        type Response = Json<Status>;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            Json(Status { healthy: true })
+        }
+    }
+
+    async fn negotiate(uri: &str, accept: &str) -> axum::response::Response {
+        use tower::ServiceExt;
+
+        let server = Server::new()
+            .with_content_negotiation()
+            .register(GetStatus)
+            .register(GetOrder)
+            .build();
+        let router: axum::Router = server.into_router().into();
+        let request = Request::get(uri)
+            .header(http::header::ACCEPT, accept)
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_content_negotiation() {
+        let response = negotiate("/status", "text/html, */*;q=0.1").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[http::header::VARY], "accept");
+
+        let response = negotiate("/status", "text/html").await;
+        assert_eq!(response.status(), http::StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(response.headers()[http::header::VARY], "accept");
+
+        // Plain-text responses are not negotiated
+        let response = negotiate("/orders/1", "text/plain, application/json;q=0.1").await;
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            response.headers()[CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+
+        // Errors are negotiated like any other JSON response
+        let response = negotiate("/orders/abc", "application/json").await;
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[http::header::VARY], "accept");
+    }
+
+    #[tokio::test]
+    async fn test_content_negotiation_passes_through_other_media_types() {
+        let server = || {
+            Server::new()
+                .with_openapi(OpenApiConfig::new("Spec API", "1.0.0"))
+                .with_content_negotiation()
+                .with_problem_details()
+                .register(GetOrder)
+                .build()
+        };
+        let request = |uri: &str, accept: &str| {
+            Request::get(uri)
+                .header(http::header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // Routes that never return JSON are not rejected
+        for accept in ["text/plain", "text/event-stream", "text/csv"] {
+            let (status, body) = send(server(), request("/orders/1", accept)).await;
+            assert_eq!(status, http::StatusCode::OK, "Accept: {}", accept);
+            assert_eq!(body, "1 false true".as_bytes());
+        }
+
+        // JSON errors from such routes still honor the Accept header
+        let (status, _) = send(server(), request("/orders/abc", "text/plain")).await;
+        assert_eq!(status, http::StatusCode::NOT_ACCEPTABLE);
+
+        let (status, body) = send(server(), request("/docs", "text/html")).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert!(String::from_utf8_lossy(&body).contains("<html"));
+
+        let (status, _) = send(server(), request("/openapi.json", "text/html")).await;
+        assert_eq!(status, http::StatusCode::OK);
+    }
+
+    static NEGOTIATED_CALLS: std::sync::atomic::AtomicUsize =
+        std::sync::atomic::AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct CountCalls;
+
+    impl Endpoint for CountCalls {
+        fn route(&self) -> Route {
+            Route::post("/calls").body(BodyPolicy::None)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for CountCalls {
+        type Request = ();
+        type Response = Json<Status>;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            NEGOTIATED_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Json(Status { healthy: true })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_content_negotiation_rejects_before_handler() {
+        use std::sync::atomic::Ordering;
+
+        let server = || {
+            Server::new()
+                .with_content_negotiation()
+                .register(CountCalls)
+                .build()
+        };
+        let request = |accept: &str| {
+            Request::post("/calls")
+                .header(http::header::ACCEPT, accept)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let (status, _) = send(server(), request("text/html")).await;
+        assert_eq!(status, http::StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(NEGOTIATED_CALLS.load(Ordering::SeqCst), 0);
+
+        let (status, _) = send(server(), request("application/json")).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(NEGOTIATED_CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[tokio::test]
+    async fn test_content_negotiation_yaml() {
+        let response = negotiate("/orders/abc", "application/yaml").await;
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/yaml");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("code: invalid_path"));
+
+        // Problem details have no YAML media type and stay JSON
+        let server = Server::new()
+            .with_content_negotiation()
+            .with_problem_details()
+            .register(GetOrder)
+            .build();
+        let request = Request::get("/orders/abc")
+            .header(http::header::ACCEPT, "application/yaml")
+            .body(Body::empty())
+            .unwrap();
+        let router: axum::Router = server.into_router().into();
+        let response = tower::ServiceExt::oneshot(router, request).await.unwrap();
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/problem+json");
+
+        let spec = openapi_spec(Server::new().with_content_negotiation().register(GetStatus)).await;
+        let content = &spec["paths"]["/status"]["get"]["responses"]["200"]["content"];
+        assert!(content["application/json"]["schema"].is_object());
+        assert_eq!(content["application/yaml"], content["application/json"]);
+    }
+
+    #[tokio::test]
+    async fn test_body_encodings_dispatch() {
+        let server = || {
+            Server::new()
+                .register(UpdateProfile(
+                    Route::post("/profile").encodings(&[BodyEncoding::Json, BodyEncoding::Form]),
+                ))
+                .build()
+        };
+
+        let request = json_request("POST", "/profile", r#"{"name":"ada","age":36}"#);
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada 36 []");
+
+        let request = Request::post("/profile")
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("name=ada&age=36"))
+            .unwrap();
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada 36 []");
+
+        let request = Request::post("/profile")
+            .header(CONTENT_TYPE, "text/plain")
+            .body(Body::from("ada"))
+            .unwrap();
+        let (status, body) = send(server(), request).await;
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(status, http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(error["code"], "unsupported_media_type");
+        assert!(
+            error["message"]
+                .as_str()
+                .unwrap()
+                .contains("application/json, application/x-www-form-urlencoded")
+        );
+
+        let spec = openapi_spec(Server::new().register(UpdateProfile(
+            Route::post("/profile").encodings(&[BodyEncoding::Json, BodyEncoding::Form]),
+        )))
+        .await;
+        let content = &spec["paths"]["/profile"]["post"]["requestBody"]["content"];
+        assert_eq!(
+            content["application/json"],
+            content["application/x-www-form-urlencoded"]
+        );
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[tokio::test]
+    async fn test_msgpack_body() {
+        let payload = rmp_serde::to_vec_named(&serde_json::json!({"name": "ada"})).unwrap();
+        let request = Request::post("/renames")
+            .header(CONTENT_TYPE, "application/msgpack")
+            .body(Body::from(payload))
+            .unwrap();
+        let (status, body) = send(Server::new().register(CreateRename).build(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada");
+
+        // Truncated input is malformed, a missing field is the wrong shape
+        let request = |payload: Vec<u8>| {
+            Request::post("/renames")
+                .header(CONTENT_TYPE, "application/msgpack")
+                .body(Body::from(payload))
+                .unwrap()
+        };
+        let payload = rmp_serde::to_vec_named(&serde_json::json!({"name": "ada"})).unwrap();
+        let server = || Server::new().register(CreateRename).build();
+        let (status, _, _) = error_response(server(), request(payload[..3].to_vec())).await;
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+
+        let payload = rmp_serde::to_vec_named(&serde_json::json!({"title": "ada"})).unwrap();
+        let (status, _, error) = error_response(server(), request(payload)).await;
+        assert_eq!(status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "invalid_body");
+    }
+
+    #[cfg(feature = "cbor")]
+    #[tokio::test]
+    async fn test_cbor_body() {
+        let mut payload = Vec::new();
+        ciborium::into_writer(&serde_json::json!({"name": "ada"}), &mut payload).unwrap();
+        let request = |payload: Vec<u8>| {
+            Request::post("/renames")
+                .header(CONTENT_TYPE, "application/cbor")
+                .body(Body::from(payload))
+                .unwrap()
+        };
+        let server = || Server::new().register(CreateRename).build();
+        let (status, body) = send(server(), request(payload.clone())).await;
+        assert_eq!(status, http::StatusCode::OK);
+        assert_eq!(&body[..], b"ada");
+
+        // Truncated input is malformed, a missing field is the wrong shape
+        let (status, _, _) = error_response(server(), request(payload[..3].to_vec())).await;
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+
+        let mut payload = Vec::new();
+        ciborium::into_writer(&serde_json::json!({"title": "ada"}), &mut payload).unwrap();
+        let (status, _, error) = error_response(server(), request(payload)).await;
+        assert_eq!(status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "invalid_body");
+    }
+
+    async fn error_response(
+        server: Server,
+        request: Request<Body>,
+    ) -> (http::StatusCode, HeaderMap, serde_json::Value) {
+        use tower::ServiceExt;
+
+        let router: axum::Router = server.into_router().into();
+        let response = router.oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, headers, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_not_found_and_method_not_allowed() {
+        let request = Request::get("/missing").body(Body::empty()).unwrap();
+        let (status, _, error) =
+            error_response(Server::new().register(GetStatus).build(), request).await;
+        assert_eq!(status, http::StatusCode::NOT_FOUND);
+        assert_eq!(error["error"], "not_found");
+        assert_eq!(error["code"], "not_found");
+
+        let request = Request::delete("/status").body(Body::empty()).unwrap();
+        let (status, headers, error) =
+            error_response(Server::new().register(GetStatus).build(), request).await;
+        assert_eq!(status, http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(error["code"], "method_not_allowed");
+        assert_eq!(headers[ALLOW], "GET,HEAD");
+    }
+
+    #[tokio::test]
+    async fn test_merge_built_routers() {
+        let server = || {
+            let statuses = Server::new().register(GetStatus).build().into_router();
+            let created = Server::new().register(CreateStatus).build().into_router();
+            Server::new().merge(statuses).merge(created).build()
+        };
+
+        let request = Request::get("/status").body(Body::empty()).unwrap();
+        let (status, _) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::OK);
+
+        let request = json_request("POST", "/statuses", r#"{"healthy": true}"#);
+        let (status, _) = send(server(), request).await;
+        assert_eq!(status, http::StatusCode::CREATED);
+
+        let request = Request::get("/missing").body(Body::empty()).unwrap();
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, http::StatusCode::NOT_FOUND);
+        assert_eq!(error["code"], "not_found");
+
+        let request = Request::delete("/statuses").body(Body::empty()).unwrap();
+        let (status, headers, error) = error_response(server(), request).await;
+        assert_eq!(status, http::StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(error["code"], "method_not_allowed");
+        assert_eq!(headers[ALLOW], "POST");
+    }
+
+    #[tokio::test]
+    async fn test_json_body_errors() {
+        let request = json_request("POST", "/renames", r#"{"name": 42}"#);
+        let (status, _, error) =
+            error_response(Server::new().register(CreateRename).build(), request).await;
+        assert_eq!(status, http::StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "invalid_body");
+        assert_eq!(error["details"]["path"], "name");
+
+        let request = json_request("POST", "/renames", r#"{"name": "#);
+        let (status, _, error) =
+            error_response(Server::new().register(CreateRename).build(), request).await;
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(error["error"], "bad_request");
+        assert_eq!(error["code"], "invalid_body");
+    }
+
+    #[tokio::test]
+    async fn test_map_rejection() {
+        let server = || {
+            Server::new()
+                .map_rejection(|error| Error::bad_request("rejected", error.to_string()))
+                .register(GetStatus)
+                .register(GetOrder)
+                .build()
+        };
+
+        let request = Request::get("/missing").body(Body::empty()).unwrap();
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "rejected");
+        assert!(error["message"].as_str().unwrap().starts_with("Not Found"));
+
+        let request = Request::post("/status").body(Body::empty()).unwrap();
+        let (status, headers, error) = error_response(server(), request).await;
+        assert_eq!(status, http::StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "rejected");
+        assert!(headers.contains_key(ALLOW));
+
+        let request = Request::get("/orders/abc").body(Body::empty()).unwrap();
+        let (_, _, error) = error_response(server(), request).await;
+        assert_eq!(error["code"], "rejected");
+    }
+
+    #[derive(Clone)]
+    struct CreateStatus;
+
+    impl Endpoint for CreateStatus {
+        fn route(&self) -> Route {
+            Route::post("/statuses")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for CreateStatus {
+        type Request = Status;
+        type Response = crate::api::Response<Status>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            crate::api::Response::created(ctx.req)
+        }
+    }
+
+    #[derive(Clone)]
+    struct FindStatus;
+
+    impl Endpoint for FindStatus {
+        fn route(&self) -> Route {
+            Route::get("/statuses/latest")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for FindStatus {
+        type Request = ();
+        type Response = HandlerResult<Status>;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            Error::not_found("no_status", "No status recorded").into()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_responses_inferred() {
+        let spec = openapi_spec(
+            Server::new()
+                .register(GetStatus)
+                .register(CreateStatus)
+                .register(FindStatus),
+        )
+        .await;
+        let codes = |path: &str, method: &str| -> Vec<String> {
+            let responses = spec["paths"][path][method]["responses"]
+                .as_object()
+                .unwrap();
+            responses.keys().cloned().collect()
+        };
+
+        assert_eq!(codes("/status", "get"), ["200"]);
+        assert_eq!(
+            codes("/statuses", "post"),
+            ["200", "201", "204", "400", "413", "415", "422"]
+        );
+
+        let created = &spec["paths"]["/statuses"]["post"]["responses"];
+        assert_eq!(
+            created["201"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Status"
+        );
+        assert!(created["204"].get("content").is_none());
+
+        let found = &spec["paths"]["/statuses/latest"]["get"]["responses"];
+        assert_eq!(codes("/statuses/latest", "get").len(), 13);
+        assert_eq!(
+            found["404"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Error"
+        );
+        assert_eq!(found["404"]["description"], "Not Found");
+        assert!(spec["components"]["schemas"]["Error"]["oneOf"].is_array());
+    }
+
+    #[derive(Clone)]
+    struct ArchiveStatus;
+
+    impl Endpoint for ArchiveStatus {
+        fn route(&self) -> Route {
+            Route::post("/statuses/archive")
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+                .errors(&[StatusCode::NOT_FOUND])
+                .error(StatusCode::CONFLICT, "Status already archived")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for ArchiveStatus {
+        type Request = Status;
+        type Response = HandlerResult<Status>;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            Error::conflict("archived", "Status already archived").into()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_declared_errors_documented() {
+        let spec = openapi_spec(Server::new().register(ArchiveStatus)).await;
+        let responses = &spec["paths"]["/statuses/archive"]["post"]["responses"];
+
+        let codes: Vec<&String> = responses.as_object().unwrap().keys().collect();
+        assert_eq!(codes, ["200", "400", "404", "409", "413", "415", "422"]);
+        assert_eq!(responses["404"]["description"], "Not Found");
+        assert_eq!(responses["409"]["description"], "Status already archived");
+        assert_eq!(
+            responses["409"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Error"
+        );
+    }
+
+    #[derive(Serialize, schemars::JsonSchema)]
+    #[serde(tag = "kind", rename_all = "snake_case")]
+    enum ShopError {
+        RateLimited { retry_after: u32 },
+        Invalid { reason: String },
+    }
+
+    impl From<Error> for ShopError {
+        fn from(error: Error) -> Self {
+            ShopError::Invalid {
+                reason: ApiError::code(&error).to_string(),
+            }
+        }
+    }
+
+    impl ApiError for ShopError {
+        fn status_code(&self) -> StatusCode {
+            match self {
+                ShopError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+                ShopError::Invalid { .. } => StatusCode::BAD_REQUEST,
+            }
+        }
+
+        fn code(&self) -> &str {
+            match self {
+                ShopError::RateLimited { .. } => "rate_limited",
+                ShopError::Invalid { .. } => "invalid",
+            }
+        }
+
+        fn message(&self) -> String {
+            self.code().to_string()
+        }
+
+        fn statuses() -> &'static [StatusCode] {
+            &[StatusCode::BAD_REQUEST, StatusCode::TOO_MANY_REQUESTS]
+        }
+    }
+
+    crate::impl_api_error!(ShopError);
+
+    #[derive(Clone)]
+    struct Checkout;
+
+    impl Endpoint for Checkout {
+        fn route(&self) -> Route {
+            Route::get("/checkout")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for Checkout {
+        type Request = ();
+        type Response = Result<Json<Status>, ShopError>;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            Err(ShopError::RateLimited { retry_after: 30 })
+        }
+    }
+
+    #[derive(Clone)]
+    struct Refund;
+
+    impl Endpoint for Refund {
+        fn route(&self) -> Route {
+            Route::get("/refunds/:id")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for Refund {
+        type Request = ();
+        type Response = HandlerResult<Status, ShopError>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            match ctx.path.parse::<u32>("id") {
+                Ok(_) => Ok(JsonResponse(Status { healthy: true })).into(),
+                Err(err) => err.into(),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_api_error_types() {
+        let server = || Server::new().register(Checkout).register(Refund).build();
+
+        let request = Request::get("/checkout").body(Body::empty()).unwrap();
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            body,
+            serde_json::json!({"kind": "rate_limited", "retry_after": 30})
+        );
+
+        let request = Request::get("/refunds/abc").body(Body::empty()).unwrap();
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["kind"], "invalid");
+        assert_eq!(body["reason"], "invalid_parameter");
+
+        let spec = openapi_spec(Server::new().register(Checkout).register(Refund)).await;
+        for path in ["/checkout", "/refunds/{id}"] {
+            let responses = &spec["paths"][path]["get"]["responses"];
+            assert_eq!(
+                responses["429"]["content"]["application/json"]["schema"]["$ref"],
+                "#/components/schemas/ShopError"
+            );
+            assert!(responses.get("404").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_problem_details() {
+        let server = || {
+            Server::new()
+                .with_problem_details()
+                .register(FindStatus)
+                .register(CreateRename)
+                .layer(crate::middleware::RequestId::new())
+                .build()
+        };
+
+        let request = Request::get("/statuses/latest")
+            .header("x-request-id", "req-42")
+            .body(Body::empty())
+            .unwrap();
+        let (status, headers, problem) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            headers[http::header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+        assert_eq!(
+            problem,
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Not Found",
+                "status": 404,
+                "detail": "No status recorded",
+                "instance": "req-42",
+                "code": "no_status",
+            })
+        );
+
+        let request = json_request("POST", "/renames", r#"{"name": 42}"#);
+        let (status, _, problem) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(problem["code"], "invalid_body");
+        assert_eq!(problem["path"], "name");
+        assert!(problem["instance"].is_string());
+
+        let request = Request::get("/missing").body(Body::empty()).unwrap();
+        let (status, headers, problem) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            headers[http::header::CONTENT_TYPE],
+            "application/problem+json"
+        );
+        assert_eq!(problem["code"], "not_found");
+
+        let spec = openapi_spec(Server::new().with_problem_details().register(FindStatus)).await;
+        let not_found = &spec["paths"]["/statuses/latest"]["get"]["responses"]["404"]["content"];
+        assert_eq!(
+            not_found["application/problem+json"]["schema"]["$ref"],
+            "#/components/schemas/ProblemDetails"
+        );
+        assert!(not_found.get("application/json").is_none());
+        assert!(spec["components"]["schemas"]["ProblemDetails"]["properties"]["title"].is_object());
+    }
+
+    async fn load_status(id: u32) -> Result<Status, Error> {
+        match id {
+            0 => Err(Error::not_found("no_status", "No status recorded")),
+            _ => Ok(Status { healthy: true }),
+        }
+    }
+
+    #[derive(Clone)]
+    struct LookupStatus;
+
+    impl Endpoint for LookupStatus {
+        fn route(&self) -> Route {
+            Route::get("/statuses/:id")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for LookupStatus {
+        type Request = ();
+        type Response = HandlerResult<Status>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            crate::handle! {
+                let id = ctx.path.parse::<u32>("id")?;
+                let status = load_status(id).await?;
+                Ok(status)
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_macro_awaits() {
+        let server = || Server::new().register(LookupStatus).build();
+
+        let request = Request::get("/statuses/7").body(Body::empty()).unwrap();
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, br#"{"healthy":true}"#.as_slice());
+
+        let request = Request::get("/statuses/0").body(Body::empty()).unwrap();
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(error["code"], "no_status");
+
+        let request = Request::get("/statuses/abc").body(Body::empty()).unwrap();
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_parameter");
+    }
+
+    #[derive(Clone)]
+    struct QueueExport;
+
+    impl Endpoint for QueueExport {
+        fn route(&self) -> Route {
+            Route::post("/exports").body(BodyPolicy::None)
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+                .success_status(StatusCode::ACCEPTED)
+                .response_header("Location", "URL of the export job")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for QueueExport {
+        type Request = ();
+        type Response = Reply<Status>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let reply = Reply::accepted(Status { healthy: true })
+                .location("/exports/1")
+                .etag("v1")
+                .cache_control("no-store")
+                .cookie("export=1; Path=/")
+                .cookie("theme=dark");
+            match ctx.headers.get("x-bad-header") {
+                Some(_) => reply.header("x-trace", "line\nbreak"),
+                None => reply,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reply_status_and_headers() {
+        use tower::ServiceExt;
+
+        let router: axum::Router = Server::new()
+            .register(QueueExport)
+            .build()
+            .into_router()
+            .into();
+        let request = Request::post("/exports").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let headers = response.headers();
+        assert_eq!(headers[http::header::LOCATION], "/exports/1");
+        assert_eq!(headers[http::header::ETAG], "\"v1\"");
+        assert_eq!(headers[http::header::CACHE_CONTROL], "no-store");
+        assert_eq!(headers.get_all(http::header::SET_COOKIE).iter().count(), 2);
+
+        let request = Request::post("/exports")
+            .header("x-bad-header", "1")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let redirect = Reply::<()>::see_other("/login").into_response();
+        assert_eq!(redirect.status(), StatusCode::SEE_OTHER);
+        assert_eq!(redirect.headers()[http::header::LOCATION], "/login");
+
+        let spec = openapi_spec(Server::new().register(QueueExport)).await;
+        let responses = &spec["paths"]["/exports"]["post"]["responses"];
+        assert!(responses.get("200").is_none());
+        assert_eq!(
+            responses["202"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Status"
+        );
+        assert_eq!(
+            responses["202"]["headers"]["Location"]["description"],
+            "URL of the export job"
+        );
+    }
+
+    #[derive(Clone)]
+    struct Theme;
+
+    impl Endpoint for Theme {
+        fn route(&self) -> Route {
+            Route::get("/theme")
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for Theme {
+        type Request = ();
+        type Response = Reply<String>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let theme = ctx.cookies().value("theme").unwrap_or("light").to_string();
+            Reply::ok(theme)
+                .set_cookie(Cookie::build(("seen", "a b")).path("/").http_only(true))
+                .remove_cookie(Cookie::build("legacy").path("/"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cookies() {
+        use tower::ServiceExt;
+
+        let router: axum::Router = Server::new().register(Theme).build().into_router().into();
+        let request = Request::get("/theme")
+            .header(http::header::COOKIE, "theme=dark; seen=1")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let set_cookies: Vec<_> = response
+            .headers()
+            .get_all(http::header::SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(set_cookies[0], "seen=a%20b; HttpOnly; Path=/");
+        assert!(set_cookies[1].starts_with("legacy=; Path=/; Max-Age=0; Expires="));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body, br#""dark""#.as_slice());
+    }
+
+    #[derive(Clone)]
+    struct SignIn;
+
+    impl Endpoint for SignIn {
+        fn route(&self) -> Route {
+            Route::post("/login").body(BodyPolicy::None)
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for SignIn {
+        type Request = ();
+        type Response = Result<Reply<()>, Error>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let session = ctx.session();
+            session.rotate();
+            session.insert("user", "amina")?;
+            Ok(Reply::no_content())
+        }
+    }
+
+    #[derive(Clone)]
+    struct WhoAmI;
+
+    impl Endpoint for WhoAmI {
+        fn route(&self) -> Route {
+            Route::get("/me")
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new().security(SecurityScheme::Session { cookie: "sid" })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for WhoAmI {
+        type Request = ();
+        type Response = Result<Json<String>, Error>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let session = ctx.session();
+            session.insert("visits", session.get::<u32>("visits").unwrap_or(0) + 1)?;
+            match session.get::<String>("user") {
+                Some(user) => Ok(Json(user)),
+                None => Err(Error::unauthorized("no_session", "Not signed in")),
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct SignOut;
+
+    impl Endpoint for SignOut {
+        fn route(&self) -> Route {
+            Route::post("/logout").body(BodyPolicy::None)
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for SignOut {
+        type Request = ();
+        type Response = Reply<()>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.session().destroy();
+            Reply::no_content()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sessions() {
+        use crate::middleware::{MemoryStore, Sessions};
+        use tower::ServiceExt;
+
+        let store = MemoryStore::new();
+        let sessions = || {
+            Server::new()
+                .register(SignIn)
+                .register(WhoAmI)
+                .register(SignOut)
+                .layer(Sessions::new(store.clone()).cookie_name("sid"))
+        };
+        let router: axum::Router = sessions().build().into_router().into();
+        let send = |method: &str, uri: &str, sid: Option<&str>| {
+            let mut request = Request::builder().method(method).uri(uri);
+            if let Some(sid) = sid {
+                request = request.header(http::header::COOKIE, format!("sid={}", sid));
+            }
+            router.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+        let session_id = |response: &axum::response::Response| {
+            let cookie = response.headers()[http::header::SET_COOKIE]
+                .to_str()
+                .unwrap();
+            let cookie = crate::server::cookies::Cookie::parse(cookie.to_string()).unwrap();
+            assert!(cookie.http_only().unwrap_or(false));
+            cookie.value().to_string()
+        };
+
+        // Sessions are only created once they hold a value
+        let response = send("GET", "/me", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let anonymous = session_id(&response);
+        assert_eq!(store.len(), 1);
+
+        // Logging in moves the session to a new ID
+        let response = send("POST", "/login", Some(&anonymous)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let sid = session_id(&response);
+        assert_ne!(sid, anonymous);
+        assert_eq!(store.len(), 1);
+
+        let response = send("GET", "/me", Some(&sid)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(http::header::SET_COOKIE).is_none());
+        let response = send("GET", "/me", Some(&anonymous)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = send("POST", "/logout", Some(&sid)).await.unwrap();
+        assert_eq!(session_id(&response), "");
+        let response = send("GET", "/me", Some(&sid)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let spec = openapi_spec(sessions()).await;
+        assert_eq!(
+            spec["components"]["securitySchemes"]["sessionAuth"],
+            serde_json::json!({"type": "apiKey", "in": "cookie", "name": "sid", "description": "Session cookie"})
+        );
+        assert_eq!(
+            spec["paths"]["/me"]["get"]["security"],
+            serde_json::json!([{"sessionAuth": []}])
+        );
+    }
+
+    #[cfg(feature = "secure-cookies")]
+    #[derive(Clone)]
+    struct Session;
+
+    #[cfg(feature = "secure-cookies")]
+    impl Endpoint for Session {
+        fn route(&self) -> Route {
+            Route::post("/session").body(BodyPolicy::None)
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[cfg(feature = "secure-cookies")]
+    #[async_trait::async_trait]
+    impl Handler for Session {
+        type Request = ();
+        type Response = Result<Reply<String>, Error>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let cookies = ctx.cookies();
+            if let Some(user) = cookies.get_private("user") {
+                return Ok(Reply::ok(user.value().to_string()));
+            }
+            let cookie = cookies.encrypt(Cookie::build(("user", "amina")).path("/"))?;
+            Ok(Reply::created("new".to_string()).set_cookie(cookie))
+        }
+    }
+
+    #[cfg(feature = "secure-cookies")]
+    #[tokio::test]
+    async fn test_private_cookies() {
+        use tower::ServiceExt;
+
+        let config = App::new("Test", "1.0.0", "127.0.0.1:0")
+            .with_cookie_secret("a secret that is long enough for cookie keys");
+        let router: axum::Router = Server::new()
+            .with_config(config)
+            .register(Session)
+            .build()
+            .into_router()
+            .into();
+
+        let request = Request::post("/session").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let set_cookie = response.headers()[http::header::SET_COOKIE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(!set_cookie.contains("amina"));
+
+        let request = Request::post("/session")
+            .header(http::header::COOKIE, set_cookie.split(';').next().unwrap())
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let request = Request::post("/session")
+            .header(http::header::COOKIE, "user=amina")
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let router: axum::Router = Server::new().register(Session).build().into_router().into();
+        let request = Request::post("/session").body(Body::empty()).unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[cfg(feature = "validation")]
+    #[derive(Serialize, Deserialize, JsonSchema, Validate)]
+    struct OrderLine {
+        #[validate(length(min = 1, max = 20, message = "Product name is required"))]
+        product: String,
+        #[validate(range(min = 1, max = 99))]
+        quantity: u32,
+    }
+
+    #[cfg(feature = "validation")]
+    #[derive(Deserialize, JsonSchema, Validate)]
+    struct OrderRequest {
+        #[validate(email)]
+        email: String,
+        #[validate(length(min = 1), nested)]
+        lines: Vec<OrderLine>,
+    }
+
+    #[cfg(feature = "validation")]
+    #[derive(Clone)]
+    struct PlaceOrder;
+
+    #[cfg(feature = "validation")]
+    impl Endpoint for PlaceOrder {
+        fn route(&self) -> Route {
+            Route::post("/orders")
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[cfg(feature = "validation")]
+    #[async_trait::async_trait]
+    impl Handler for PlaceOrder {
+        type Request = OrderRequest;
+        type Response = Json<u32>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            Json(ctx.req.lines.iter().map(|line| line.quantity).sum())
+        }
+    }
+
+    #[cfg(feature = "validation")]
+    #[tokio::test]
+    async fn test_register_validated() {
+        let post = |body: &'static str| {
+            Request::post("/orders")
+                .header(http::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let server = || Server::new().register_validated(PlaceOrder).build();
+        let request = post(r#"{"email":"a@b.co","lines":[{"product":"tea","quantity":2}]}"#);
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"2".as_slice());
+
+        let request = post(
+            r#"{"email":"nope","lines":[{"product":"tea","quantity":1},{"product":"","quantity":0}]}"#,
+        );
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "validation_failed");
+        assert_eq!(
+            error["details"],
+            serde_json::json!({
+                "email": ["Failed email validation"],
+                "lines[1].product": ["Product name is required"],
+                "lines[1].quantity": ["Failed range validation"],
+            })
+        );
+
+        // Plain registration leaves validation to the handler
+        let server = Server::new().register(PlaceOrder).build();
+        let (status, _) = send(server, post(r#"{"email":"nope","lines":[]}"#)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let spec = openapi_spec(Server::new().register_validated(PlaceOrder)).await;
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(
+            schemas["OrderRequest"]["properties"]["email"]["format"],
+            "email"
+        );
+        assert_eq!(
+            schemas["OrderRequest"]["properties"]["lines"]["minItems"],
+            1
+        );
+        assert_eq!(
+            schemas["OrderLine"]["properties"]["product"]["maxLength"],
+            20
+        );
+        assert_eq!(
+            schemas["OrderLine"]["properties"]["quantity"]["maximum"],
+            99.0
+        );
+    }
+
+    #[derive(Clone)]
+    struct SearchStock;
+
+    impl Endpoint for SearchStock {
+        fn route(&self) -> Route {
+            let mut route = Route::get("/stock/:sku");
+            route.path_param("sku").pattern(r"^[A-Z]{3}-\d+$");
+            route.query("page").schema::<u32>().min(1).max(100);
+            route.query("limit").schema::<u32>();
+            route.query("q").min_length(2).max_length(10);
+            route.query("sort").enum_values(["name", "price"]);
+            route.query("store").required();
+            route
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for SearchStock {
+        type Request = ();
+        type Response = &'static str;
+
+        async fn handle(&self, _ctx: Context<Self::Request>) -> Self::Response {
+            "found"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_param_constraints() {
+        let server = || Server::new().register(SearchStock).build();
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        let request = get("/stock/ABC-12?store=1&page=3&q=tea&sort=price");
+        let (status, body) = send(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"found".as_slice());
+
+        let request = get("/stock/abc?page=0&q=t&sort=size");
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "validation_failed");
+        assert_eq!(
+            error["details"],
+            serde_json::json!({
+                "page": ["Must be at least 1"],
+                "q": ["Must be at least 2 characters long"],
+                "sku": ["Must match the pattern `^[A-Z]{3}-\\d+$`"],
+                "sort": ["Must be one of: name, price"],
+                "store": ["Parameter is required"],
+            })
+        );
+
+        let request = get("/stock/ABC-1?store=1&page=two");
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["details"]["page"][0], "Must be an integer");
+
+        // Bounds of the schema type are enforced without explicit ones
+        let request = get("/stock/ABC-1?store=1&limit=-1");
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["details"]["limit"][0], "Must be at least 0");
+
+        let request = get("/stock/ABC-1?store=1&limit=5000000000");
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["details"]["limit"][0], "Must be at most 4294967295");
+
+        let spec = openapi_spec(Server::new().register(SearchStock)).await;
+        let operation = &spec["paths"]["/stock/{sku}"]["get"];
+        let schema = |name: &str| {
+            let params = operation["parameters"].as_array().unwrap();
+            let param = params.iter().find(|param| param["name"] == name).unwrap();
+            param["schema"].clone()
+        };
+        assert_eq!(schema("sku")["pattern"], r"^[A-Z]{3}-\d+$");
+        assert_eq!(schema("page")["minimum"], 1.0);
+        assert_eq!(schema("page")["maximum"], 100.0);
+        assert_eq!(schema("q")["maxLength"], 10);
+        assert_eq!(schema("sort")["enum"], serde_json::json!(["name", "price"]));
+        assert!(operation["responses"]["422"].is_object());
+    }
+
+    #[derive(Clone)]
+    struct ListNumbers;
+
+    impl Endpoint for ListNumbers {
+        fn route(&self) -> Route {
+            Route::get("/numbers").paginate(Paginate::offset().default_limit(5).max_limit(10))
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for ListNumbers {
+        type Request = ();
+        type Response = Page<u64>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let page = ctx.pagination();
+            let end = (page.offset() + u64::from(page.limit())).min(23);
+            page.page((page.offset()..end).collect()).total(23)
+        }
+    }
+
+    #[derive(Clone)]
+    struct NumberFeed;
+
+    impl Endpoint for NumberFeed {
+        fn route(&self) -> Route {
+            Route::get("/feed").paginate(Paginate::cursor())
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for NumberFeed {
+        type Request = ();
+        type Response = Page<u64>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let page = ctx.pagination();
+            let start: u64 = page.cursor().and_then(|c| c.parse().ok()).unwrap_or(0);
+            let items = (start..start + u64::from(page.limit())).collect();
+            let page = page.page(items).next_cursor((start + 20).to_string());
+            if start > 0 {
+                page.prev_cursor((start - 20).to_string())
+            } else {
+                page
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pagination() {
+        let server = || {
+            Server::new()
+                .register(ListNumbers)
+                .register(NumberFeed)
+                .build()
+        };
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        let (status, headers, page) =
+            error_response(server(), get("/numbers?offset=20&limit=50")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            page,
+            serde_json::json!({ "items": [20, 21, 22], "limit": 10, "offset": 20, "total": 23 })
+        );
+        assert_eq!(
+            headers["link"],
+            "</numbers?offset=0&limit=10>; rel=\"first\", \
+             </numbers?offset=10&limit=10>; rel=\"prev\", \
+             </numbers?offset=20&limit=10>; rel=\"last\""
+        );
+
+        let (_, _, page) = error_response(server(), get("/numbers")).await;
+        assert_eq!(page["items"], serde_json::json!([0, 1, 2, 3, 4]));
+
+        let (status, _, error) = error_response(server(), get("/numbers?offset=abc")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_query");
+        assert_eq!(
+            error["message"],
+            "Query parameter `offset` must be a non-negative integer"
+        );
+
+        let (status, _, error) = error_response(server(), get("/numbers?limit=-5")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error["message"],
+            "Query parameter `limit` must be a positive integer"
+        );
+
+        let (_, headers, page) = error_response(server(), get("/feed?cursor=20&limit=2")).await;
+        assert_eq!(
+            page,
+            serde_json::json!({ "items": [20, 21], "limit": 2, "next": "40", "prev": "0" })
+        );
+        assert_eq!(
+            headers["link"],
+            "</feed?limit=2>; rel=\"first\", \
+             </feed?cursor=0&limit=2>; rel=\"prev\", \
+             </feed?cursor=40&limit=2>; rel=\"next\""
+        );
+
+        let builder = Server::new().register(ListNumbers).register(NumberFeed);
+        let spec = openapi_spec(builder).await;
+        let param = |path: &str, name: &str| {
+            let params = spec["paths"][path]["get"]["parameters"].as_array().unwrap();
+            params
+                .iter()
+                .find(|param| param["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(param("/numbers", "offset")["schema"]["default"], 0);
+        assert_eq!(param("/numbers", "limit")["schema"]["default"], 5);
+        assert_eq!(param("/numbers", "limit")["schema"]["maximum"], 10.0);
+        assert_eq!(param("/feed", "cursor")["schema"]["type"], "string");
+
+        let response = &spec["paths"]["/numbers"]["get"]["responses"]["200"];
+        assert!(response["headers"]["Link"].is_object());
+        let schema = response["content"]["application/json"]["schema"]["$ref"]
+            .as_str()
+            .unwrap()
+            .rsplit('/')
+            .next()
+            .unwrap();
+        let properties = &spec["components"]["schemas"][schema]["properties"];
+        assert_eq!(properties["items"]["type"], "array");
+        assert!(properties["next"].is_object());
+        assert!(properties.get("pagination").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pagination_links_in_nested_routers() {
+        use tower::ServiceExt;
+
+        let numbers = || Server::new().register(ListNumbers).build().into_router();
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        let server = Server::new().nest("/api", numbers()).build();
+        let (status, headers, _) = error_response(server, get("/api/numbers?offset=5")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            headers["link"],
+            "</api/numbers?offset=0&limit=5>; rel=\"first\", \
+             </api/numbers?offset=0&limit=5>; rel=\"prev\", \
+             </api/numbers?offset=10&limit=5>; rel=\"next\", \
+             </api/numbers?offset=20&limit=5>; rel=\"last\""
+        );
+
+        let router = axum::Router::new().nest("/v1", numbers().into());
+        let response = router.oneshot(get("/v1/numbers?offset=20")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()["link"],
+            "</v1/numbers?offset=0&limit=5>; rel=\"first\", \
+             </v1/numbers?offset=15&limit=5>; rel=\"prev\", \
+             </v1/numbers?offset=20&limit=5>; rel=\"last\""
+        );
+    }
+
+    #[derive(Clone)]
+    struct ListOrders;
+
+    impl Endpoint for ListOrders {
+        fn route(&self) -> Route {
+            Route::get("/orders")
+                .filter(
+                    FilterSpec::new()
+                        .field("status", [FilterOp::Eq, FilterOp::In])
+                        .field("total", [FilterOp::Gte])
+                        .sortable(["created_at", "total"])
+                        .default_sort("-created_at"),
+                )
+                .paginate(Paginate::offset())
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for ListOrders {
+        type Request = ();
+        type Response = Json<Vec<String>>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let query = ctx.list_query();
+            let filters = query.filters().iter().map(|filter| {
+                format!(
+                    "{} {} {}",
+                    filter.field,
+                    filter.op.as_sql(),
+                    filter.values.join("|")
+                )
+            });
+            let sort = query
+                .sort()
+                .iter()
+                .map(|key| format!("ORDER BY {} {}", key.field, key.direction.as_sql()));
+            let address = ctx
+                .query
+                .get_object("address")
+                .map(|address| format!("address {}", address));
+            Json(filters.chain(sort).chain(address).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_filter_and_sort() {
+        let server = || Server::new().register(ListOrders).build();
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        let request = get("/orders?status%5Bin%5D=paid,shipped&total[gte]=10&limit=5&sort=total");
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!([
+                "status IN paid|shipped",
+                "total >= 10",
+                "ORDER BY total ASC"
+            ])
+        );
+
+        let (_, _, body) = error_response(server(), get("/orders?status=paid")).await;
+        assert_eq!(
+            body,
+            serde_json::json!(["status = paid", "ORDER BY created_at DESC"])
+        );
+
+        // Nested objects are left to the handler
+        let request = get("/orders?status=paid&address[city]=Paris&filter[owner]=me");
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!([
+                "status = paid",
+                "ORDER BY created_at DESC",
+                r#"address {"city":"Paris"}"#
+            ])
+        );
+
+        let (status, _, error) = error_response(server(), get("/orders?status[near]=1")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(error["code"], "invalid_filter");
+
+        let (_, _, error) = error_response(server(), get("/orders?total[lt]=1")).await;
+        assert_eq!(
+            error["message"],
+            "Operator `lt` is not supported on `total`"
+        );
+
+        let (_, _, error) = error_response(server(), get("/orders?sort=-owner")).await;
+        assert_eq!(error["code"], "invalid_sort");
+
+        let spec = openapi_spec(Server::new().register(ListOrders)).await;
+        let operation = &spec["paths"]["/orders"]["get"];
+        let names: Vec<_> = operation["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "offset",
+                "limit",
+                "status",
+                "status[in]",
+                "total[gte]",
+                "sort"
+            ]
+        );
+        assert!(operation["responses"]["400"].is_object());
+    }
+
+    #[derive(Clone)]
+    struct SearchPosts;
+
+    impl Endpoint for SearchPosts {
+        fn route(&self) -> Route {
+            let mut route = Route::get("/posts");
+            route.query("tag").schema::<Vec<String>>().max_length(5);
+            route
+                .query("ids")
+                .schema::<Vec<u32>>()
+                .min(1)
+                .style(ParamStyle::CommaSeparated);
+            route
+                .query("filter")
+                .schema::<HashMap<String, String>>()
+                .required();
+            route
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for SearchPosts {
+        type Request = ();
+        type Response = Json<serde_json::Value>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            Json(serde_json::json!({
+                "tags": ctx.query.get_all("tag"),
+                "ids": ctx.query.parse_list::<u32>("ids").unwrap(),
+                "filter": ctx.query.get_object("filter"),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multi_value_query_params() {
+        let server = || Server::new().register(SearchPosts).build();
+        let get = |uri: &str| Request::get(uri).body(Body::empty()).unwrap();
+
+        let request = get("/posts?tag=rust&tag[]=web&ids=1,2&ids=3&filter[status]=draft");
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "tags": ["rust", "web"],
+                "ids": [1, 2, 3],
+                "filter": { "status": "draft" },
+            })
+        );
+
+        let request = get("/posts?tag=rust&tag=toolong&ids=1,0,x");
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error["details"],
+            serde_json::json!({
+                "filter": ["Parameter is required"],
+                "ids": ["Must be at least 1", "Must be an integer"],
+                "tag": ["Must be at most 5 characters long"],
+            })
+        );
+
+        let spec = openapi_spec(Server::new().register(SearchPosts)).await;
+        let params = spec["paths"]["/posts"]["get"]["parameters"].clone();
+        let param = |name: &str| {
+            let params = params.as_array().unwrap();
+            params
+                .iter()
+                .find(|param| param["name"] == name)
+                .unwrap()
+                .clone()
+        };
+        assert_eq!(param("tag")["style"], "form");
+        assert_eq!(param("tag")["explode"], true);
+        assert_eq!(param("tag")["schema"]["items"]["maxLength"], 5);
+        assert_eq!(param("ids")["explode"], false);
+        assert_eq!(param("ids")["schema"]["items"]["minimum"], 1.0);
+        assert_eq!(param("filter")["style"], "deepObject");
+        assert_eq!(param("filter")["explode"], true);
+    }
+
+    #[derive(Clone)]
+    struct ReplaceDocument;
+
+    impl Endpoint for ReplaceDocument {
+        fn route(&self) -> Route {
+            let mut route = Route::put("/documents/:id");
+            route
+                .header("X-Tenant-Id")
+                .required()
+                .schema::<u64>()
+                .desc("Tenant owning the document");
+            route.header("If-Match").pattern(r#"^"[a-z0-9]+"$"#);
+            route.cookie("locale").required().enum_values(["en", "sw"]);
+            route.body(BodyPolicy::None)
+        }
+
+        fn meta(&self) -> Meta {
+            Meta::new()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for ReplaceDocument {
+        type Request = ();
+        type Response = Result<Json<serde_json::Value>, Error>;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            let tenant: u64 = ctx.parse_header("X-Tenant-Id")?;
+            Ok(Json(serde_json::json!({
+                "tenant": tenant,
+                "if_match": ctx.header("if-match"),
+                "locale": ctx.cookie("locale"),
+            })))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_header_and_cookie_params() {
+        let server = || Server::new().register(ReplaceDocument).build();
+        let put = |headers: &[(&str, &str)]| {
+            let mut request = Request::put("/documents/1");
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        let request = put(&[
+            ("x-tenant-id", "7"),
+            ("if-match", "\"v2\""),
+            ("cookie", "locale=sw"),
+        ]);
+        let (status, _, body) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({ "tenant": 7, "if_match": "\"v2\"", "locale": "sw" })
+        );
+
+        // Missing headers and cookies are reported like missing query parameters
+        let (status, _, error) = error_response(server(), put(&[("cookie", "locale=sw")])).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error["code"], "validation_failed");
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "X-Tenant-Id": ["Parameter is required"] })
+        );
+
+        let (status, _, error) = error_response(server(), put(&[("x-tenant-id", "7")])).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error["details"],
+            serde_json::json!({ "locale": ["Parameter is required"] })
+        );
+
+        let request = put(&[
+            ("x-tenant-id", "acme"),
+            ("if-match", "v2"),
+            ("cookie", "locale=fr"),
+        ]);
+        let (status, _, error) = error_response(server(), request).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            error["details"],
+            serde_json::json!({
+                "If-Match": ["Must match the pattern `^\"[a-z0-9]+\"$`"],
+                "X-Tenant-Id": ["Must be an integer"],
+                "locale": ["Must be one of: en, sw"],
+            })
+        );
+
+        let spec = openapi_spec(Server::new().register(ReplaceDocument)).await;
+        let operation = &spec["paths"]["/documents/{id}"]["put"];
+        let params = operation["parameters"].as_array().unwrap();
+        let param = |name: &str| params.iter().find(|param| param["name"] == name).unwrap();
+        assert_eq!(param("X-Tenant-Id")["in"], "header");
+        assert_eq!(param("X-Tenant-Id")["required"], true);
+        assert_eq!(param("X-Tenant-Id")["schema"]["type"], "integer");
+        assert_ne!(param("If-Match")["required"], true);
+        assert_eq!(param("locale")["in"], "cookie");
+        assert!(operation["responses"]["400"].is_object());
+    }
+
+    /// Middleware that appends its name to the `x-order` request header.
+    fn record_order(
+        name: &'static str,
+    ) -> impl Fn(
+        Request<Body>,
+        axum::middleware::Next,
+    ) -> futures::future::BoxFuture<'static, axum::response::Response>
+    + Clone
+    + Send
+    + Sync
+    + 'static {
+        move |mut request: Request<Body>, next: axum::middleware::Next| {
+            let order = match request.headers().get("x-order") {
+                Some(order) => format!("{},{}", order.to_str().unwrap(), name),
+                None => name.to_string(),
+            };
+            request
+                .headers_mut()
+                .insert("x-order", order.parse().unwrap());
+            Box::pin(next.run(request))
+        }
+    }
+
+    #[derive(Clone)]
+    struct AdminStats;
+
+    impl Endpoint for AdminStats {
+        fn route(&self) -> Route {
+            Route::get("/admin/stats")
+        }
+
+        fn layers(&self) -> Layers {
+            async fn validate(token: String) -> Result<(), String> {
+                match token.as_str() {
+                    "secret" => Ok(()),
+                    _ => Err("Invalid token".to_string()),
+                }
+            }
+
+            Layers::new()
+                .middleware(record_order("first"))
+                .middleware(record_order("second"))
+                .layer(crate::middleware::BearerAuth::new(validate))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for AdminStats {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.header("x-order").unwrap_or_default().to_string()
+        }
+    }
+
+    #[derive(Clone)]
+    struct PublicStats;
+
+    impl Endpoint for PublicStats {
+        fn route(&self) -> Route {
+            Route::get("/stats")
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Handler for PublicStats {
+        type Request = ();
+        type Response = String;
+
+        async fn handle(&self, ctx: Context<Self::Request>) -> Self::Response {
+            ctx.header("x-order").unwrap_or_default().to_string()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_layers() {
+        let server = || {
+            Server::new()
+                .register(AdminStats)
+                .register(PublicStats)
+                .middleware(record_order("global"))
+                .build()
+        };
+        let get = |uri: &str, token: Option<&str>| {
+            let mut request = Request::get(uri);
+            if let Some(token) = token {
+                request = request.header("authorization", format!("Bearer {}", token));
+            }
+            request.body(Body::empty()).unwrap()
+        };
+
+        // Endpoint layers apply to their route only
+        let (status, body) = send(server(), get("/stats", None)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"global".as_slice());
+
+        let (status, _) = send(server(), get("/admin/stats", None)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(server(), get("/admin/stats", Some("wrong"))).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Other methods on the path are answered with 405 outside the endpoint layers
+        let request = Request::delete("/admin/stats").body(Body::empty()).unwrap();
+        let (status, _) = send(server(), request).await;
+        assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+
+        // Server layers run first, then endpoint layers from the last added to the first
+        let (status, body) = send(server(), get("/admin/stats", Some("secret"))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"global,second,first".as_slice());
+    }
 }
@@ -0,0 +1,193 @@
+use axum::http::{HeaderMap, header};
+use cookie::CookieJar;
+
+pub use cookie::{Cookie, SameSite};
+
+#[cfg(feature = "secure-cookies")]
+pub use cookie::Key;
+
+#[cfg(feature = "secure-cookies")]
+use crate::api::response::Error;
+
+/// Key used to sign and encrypt cookies, stored in the request extensions.
+#[cfg(feature = "secure-cookies")]
+#[derive(Clone)]
+pub(crate) struct CookieKey(pub(crate) Key);
+
+/// Cookies sent with a request, read with `ctx.cookies()`.
+///
+/// Cookies are set and removed on the response with
+/// [`Reply::set_cookie()`](crate::api::Reply::set_cookie) and
+/// [`Reply::remove_cookie()`](crate::api::Reply::remove_cookie).
+///
+/// With the `secure-cookies` cargo feature and a key configured with
+/// `ServerBuilder::with_cookie_key()` or `App::with_cookie_secret()`, cookies
+/// can also be signed (tamper-proof) or private (encrypted and tamper-proof):
+/// - `cookies.sign(cookie)?` / `cookies.get_signed("session")`
+/// - `cookies.encrypt(cookie)?` / `cookies.get_private("session")`
+#[derive(Clone, Default)]
+pub struct Cookies {
+    jar: CookieJar,
+    #[cfg(feature = "secure-cookies")]
+    key: Option<Key>,
+}
+
+impl Cookies {
+    /// Parse the `Cookie` headers of a request, skipping malformed cookies
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let mut jar = CookieJar::new();
+        let cookies = headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| Cookie::split_parse_encoded(value.to_owned()))
+            .filter_map(Result::ok);
+        for cookie in cookies {
+            jar.add_original(cookie);
+        }
+
+        Self {
+            jar,
+            #[cfg(feature = "secure-cookies")]
+            key: None,
+        }
+    }
+
+    /// Use a key for signed and private cookies
+    #[cfg(feature = "secure-cookies")]
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Get a cookie by name
+    pub fn get(&self, name: &str) -> Option<&Cookie<'static>> {
+        self.jar.get(name)
+    }
+
+    /// Get the value of a cookie by name
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.jar.get(name).map(Cookie::value)
+    }
+
+    /// Iterate over all cookies
+    pub fn iter(&self) -> impl Iterator<Item = &Cookie<'static>> {
+        self.jar.iter()
+    }
+
+    /// Number of cookies
+    pub fn len(&self) -> usize {
+        self.jar.iter().count()
+    }
+
+    /// Check if the request has no cookies
+    pub fn is_empty(&self) -> bool {
+        self.jar.iter().next().is_none()
+    }
+
+    /// Get a signed cookie, verifying its signature.
+    ///
+    /// Returns `None` if the cookie is missing, its signature does not match, or no
+    /// key is configured.
+    #[cfg(feature = "secure-cookies")]
+    pub fn get_signed(&self, name: &str) -> Option<Cookie<'static>> {
+        self.jar.signed(self.key.as_ref()?).get(name)
+    }
+
+    /// Get a private cookie, decrypting and verifying its value.
+    ///
+    /// Returns `None` if the cookie is missing, cannot be decrypted, or no key is
+    /// configured.
+    #[cfg(feature = "secure-cookies")]
+    pub fn get_private(&self, name: &str) -> Option<Cookie<'static>> {
+        self.jar.private(self.key.as_ref()?).get(name)
+    }
+
+    /// Sign a cookie so that its value can be read but not modified by the client.
+    ///
+    /// Fails with a 500 error if no key is configured.
+    #[cfg(feature = "secure-cookies")]
+    pub fn sign(&self, cookie: impl Into<Cookie<'static>>) -> Result<Cookie<'static>, Error> {
+        let key = self.key()?;
+        let mut jar = CookieJar::new();
+        jar.signed_mut(key).add(cookie);
+        Ok(jar.delta().next().cloned().expect("cookie was just added"))
+    }
+
+    /// Encrypt a cookie so that its value can be neither read nor modified by the client.
+    ///
+    /// Fails with a 500 error if no key is configured.
+    #[cfg(feature = "secure-cookies")]
+    pub fn encrypt(&self, cookie: impl Into<Cookie<'static>>) -> Result<Cookie<'static>, Error> {
+        let key = self.key()?;
+        let mut jar = CookieJar::new();
+        jar.private_mut(key).add(cookie);
+        Ok(jar.delta().next().cloned().expect("cookie was just added"))
+    }
+
+    #[cfg(feature = "secure-cookies")]
+    fn key(&self) -> Result<&Key, Error> {
+        self.key.as_ref().ok_or_else(|| {
+            tracing::error!(
+                "Signed and private cookies need a key. Call .with_cookie_key() on the server builder."
+            );
+            Error::internal("cookie_key_missing", "Internal server error")
+        })
+    }
+}
+
+impl std::fmt::Debug for Cookies {
+    // Keeps the key out of debug output
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.jar.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_cookies_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; lang=sw"),
+        );
+        headers.append(
+            header::COOKIE,
+            HeaderValue::from_static("name=J%C3%BClia; =bad"),
+        );
+
+        let cookies = Cookies::from_headers(&headers);
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies.value("theme"), Some("dark"));
+        assert_eq!(cookies.value("name"), Some("Jülia"));
+        assert!(cookies.get("missing").is_none());
+        assert!(Cookies::default().is_empty());
+    }
+
+    #[cfg(feature = "secure-cookies")]
+    #[test]
+    fn test_signed_and_private_cookies() {
+        let cookies = Cookies::default().with_key(Key::generate());
+        let signed = cookies.sign(("user", "42")).unwrap();
+        let private = cookies.encrypt(("session", "abc")).unwrap();
+        assert_ne!(signed.value(), "42");
+        assert!(!private.value().contains("abc"));
+
+        let mut headers = HeaderMap::new();
+        let header = format!("{}; {}; forged=1", signed.encoded(), private.encoded());
+        headers.insert(header::COOKIE, HeaderValue::try_from(header).unwrap());
+        let key = cookies.key.clone().unwrap();
+        let received = Cookies::from_headers(&headers).with_key(key);
+
+        assert_eq!(received.get_signed("user").unwrap().value(), "42");
+        assert_eq!(received.get_private("session").unwrap().value(), "abc");
+        assert!(received.get_signed("forged").is_none());
+        assert!(received.get_private("user").is_none());
+
+        assert!(Cookies::default().sign(("user", "42")).is_err());
+    }
+}
@@ -3,8 +3,30 @@
 //! This module provides the [`Endpoint`] trait and supporting types for defining
 //! API routes with automatic OpenAPI documentation generation.
 
+use std::convert::Infallible;
+
+use aide::axum::routing::ApiMethodRouter;
+use aide::openapi::Operation;
+use axum::body::Body;
+use axum::routing::MethodRouter;
+use http::Request;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tower::Service;
+
+use crate::api::filter::FilterSpec;
+use crate::api::pagination::{Paginate, PaginationMode};
+use crate::server::params::TypedValue;
+
 /// HTTP method types for REST API endpoints.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Standard methods have dedicated variants. Any other method, such as WebDAV's
+/// `PROPFIND`, can be routed with [`HttpMethod::Other`] or by passing an
+/// [`http::Method`] to [`Route::new()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HttpMethod {
     GET,
     POST,
@@ -13,11 +35,19 @@ pub enum HttpMethod {
     DELETE,
     OPTIONS,
     HEAD,
+    TRACE,
+    /// Extension method without a dedicated variant.
+    ///
+    /// Extension methods are routed but cannot be represented in the OpenAPI
+    /// specification, so they are omitted from the generated documentation.
+    Other(http::Method),
 }
 
 impl HttpMethod {
     /// Returns the lowercase string representation for routing.
-    pub fn as_str(&self) -> &'static str {
+    ///
+    /// Extension methods are returned as written, since method names are case-sensitive.
+    pub fn as_str(&self) -> &str {
         match self {
             Self::GET => "get",
             Self::POST => "post",
@@ -26,16 +56,209 @@ impl HttpMethod {
             Self::DELETE => "delete",
             Self::OPTIONS => "options",
             Self::HEAD => "head",
+            Self::TRACE => "trace",
+            Self::Other(method) => method.as_str(),
+        }
+    }
+
+    /// Returns the corresponding [`http::Method`].
+    pub fn to_method(&self) -> http::Method {
+        match self {
+            Self::GET => http::Method::GET,
+            Self::POST => http::Method::POST,
+            Self::PUT => http::Method::PUT,
+            Self::PATCH => http::Method::PATCH,
+            Self::DELETE => http::Method::DELETE,
+            Self::OPTIONS => http::Method::OPTIONS,
+            Self::HEAD => http::Method::HEAD,
+            Self::TRACE => http::Method::TRACE,
+            Self::Other(method) => method.clone(),
+        }
+    }
+}
+
+impl From<http::Method> for HttpMethod {
+    fn from(method: http::Method) -> Self {
+        match method {
+            http::Method::GET => Self::GET,
+            http::Method::POST => Self::POST,
+            http::Method::PUT => Self::PUT,
+            http::Method::PATCH => Self::PATCH,
+            http::Method::DELETE => Self::DELETE,
+            http::Method::OPTIONS => Self::OPTIONS,
+            http::Method::HEAD => Self::HEAD,
+            http::Method::TRACE => Self::TRACE,
+            method => Self::Other(method),
+        }
+    }
+}
+
+/// Whether a route reads a request body.
+///
+/// The policy controls how the body is extracted and whether the OpenAPI
+/// `requestBody` is documented as required. Each method has a default, which
+/// can be changed with [`Route::body()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyPolicy {
+    /// The body is never read and is not documented.
+    ///
+    /// The handler receives the empty request value. Default for `GET`, `HEAD`,
+    /// `OPTIONS` and `TRACE`.
+    None,
+    /// The body is read when present; an empty body yields the empty request value.
+    ///
+    /// Default for `DELETE` and extension methods.
+    Optional,
+    /// The body must be present; an empty body is rejected with a 400 `missing_body` error.
+    ///
+    /// Default for `POST`, `PUT` and `PATCH`.
+    Required,
+}
+
+impl BodyPolicy {
+    /// Returns the default policy for an HTTP method.
+    pub fn for_method(method: &HttpMethod) -> Self {
+        match method {
+            HttpMethod::POST | HttpMethod::PUT | HttpMethod::PATCH => Self::Required,
+            HttpMethod::DELETE | HttpMethod::Other(_) => Self::Optional,
+            HttpMethod::GET | HttpMethod::HEAD | HttpMethod::OPTIONS | HttpMethod::TRACE => {
+                Self::None
+            }
         }
     }
 }
 
+/// Encoding of a route's request body.
+///
+/// Selects how the body is deserialized into [`Handler::Request`](crate::api::Handler::Request)
+/// and which media types are documented in the OpenAPI `requestBody`. A route accepts
+/// JSON and every enabled binary format by default; requests whose `Content-Type`
+/// matches none of the route's encodings are rejected with 415 Unsupported Media Type.
+///
+/// MessagePack and CBOR are enabled with the `msgpack` and `cbor` cargo features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyEncoding {
+    /// `application/json` (default)
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded`
+    Form,
+    /// `multipart/form-data`
+    ///
+    /// Text fields are deserialized into the request type like a urlencoded form.
+    /// File fields are collected into [`Uploads`](crate::server::Uploads), available
+    /// through `ctx.uploads()`.
+    Multipart,
+    /// `application/msgpack`
+    #[cfg(feature = "msgpack")]
+    MsgPack,
+    /// `application/cbor`
+    #[cfg(feature = "cbor")]
+    Cbor,
+}
+
+impl BodyEncoding {
+    /// Encodings accepted by routes that do not call [`Route::encoding()`].
+    ///
+    /// Forms are left out because browsers can submit them cross-site without a
+    /// CORS preflight; opt into them per route.
+    pub const DEFAULT: &'static [BodyEncoding] = &[
+        Self::Json,
+        #[cfg(feature = "msgpack")]
+        Self::MsgPack,
+        #[cfg(feature = "cbor")]
+        Self::Cbor,
+    ];
+
+    /// Returns the media type for this encoding.
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Form => "application/x-www-form-urlencoded",
+            Self::Multipart => "multipart/form-data",
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => "application/msgpack",
+            #[cfg(feature = "cbor")]
+            Self::Cbor => "application/cbor",
+        }
+    }
+
+    /// Returns whether a `Content-Type` header value matches this encoding.
+    ///
+    /// JSON also accepts structured syntax suffixes such as `application/problem+json`,
+    /// and MessagePack accepts `application/x-msgpack` and `application/vnd.msgpack`.
+    pub fn matches(&self, content_type: &str) -> bool {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+
+        match self {
+            Self::Json => {
+                essence == "application/json"
+                    || (essence.starts_with("application/") && essence.ends_with("+json"))
+            }
+            #[cfg(feature = "msgpack")]
+            Self::MsgPack => matches!(
+                essence.as_str(),
+                "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+            ),
+            _ => essence == self.media_type(),
+        }
+    }
+}
+
+/// How a query parameter is written in the query string.
+///
+/// Documented as the OpenAPI `style` and `explode` settings of the parameter. Set
+/// with [`ParamBuilder::style()`]; by default array schemas use [`ParamStyle::Repeated`],
+/// object schemas [`ParamStyle::DeepObject`] and other schemas [`ParamStyle::Form`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamStyle {
+    /// A single value: `?page=2` (`style: form`)
+    Form,
+    /// One key per value: `?tag=a&tag=b`, also accepting `?tag[]=a&tag[]=b`
+    /// (`style: form, explode: true`)
+    Repeated,
+    /// Comma-separated values: `?tag=a,b` (`style: form, explode: false`)
+    CommaSeparated,
+    /// Bracketed object keys: `?filter[status]=active` (`style: deepObject, explode: true`)
+    DeepObject,
+}
+
 /// Query parameter metadata for OpenAPI documentation.
 #[derive(Debug, Clone)]
 pub struct QueryParam {
     pub name: &'static str,
     pub description: Option<&'static str>,
     pub required: bool,
+    /// Schema information for the parameter value
+    pub schema: ParamSchema,
+    /// Query string style set with [`ParamBuilder::style()`], inferred from the schema if unset
+    pub style: Option<ParamStyle>,
+}
+
+impl QueryParam {
+    /// Returns the declared style, or the style implied by the schema.
+    pub fn resolved_style(&self) -> ParamStyle {
+        self.style.unwrap_or_else(|| {
+            match self
+                .schema
+                .json_schema
+                .as_ref()
+                .and_then(|schema| schema.instance_type.as_ref())
+            {
+                Some(SingleOrVec::Single(instance_type)) => match **instance_type {
+                    InstanceType::Array => ParamStyle::Repeated,
+                    InstanceType::Object => ParamStyle::DeepObject,
+                    _ => ParamStyle::Form,
+                },
+                _ => ParamStyle::Form,
+            }
+        })
+    }
 }
 
 /// Path parameter metadata for OpenAPI documentation.
@@ -44,12 +267,255 @@ pub struct PathParam {
     pub name: &'static str,
     pub description: Option<&'static str>,
     pub required: bool,
+    /// Schema information for the parameter value
+    pub schema: ParamSchema,
+}
+
+/// Header parameter metadata, declared with [`Route::header()`].
+#[derive(Debug, Clone)]
+pub struct HeaderParam {
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+    pub required: bool,
+    /// Schema information for the parameter value
+    pub schema: ParamSchema,
+}
+
+/// Cookie parameter metadata, declared with [`Route::cookie()`].
+#[derive(Debug, Clone)]
+pub struct CookieParam {
+    pub name: &'static str,
+    pub description: Option<&'static str>,
+    pub required: bool,
+    /// Schema information for the parameter value
+    pub schema: ParamSchema,
+}
+
+/// Schema information for a route parameter.
+///
+/// Parameters without an explicit schema are documented as strings. Constraints
+/// (bounds, lengths, pattern and enum values) are documented as schema keywords and
+/// enforced before the handler runs.
+#[derive(Debug, Clone, Default)]
+pub struct ParamSchema {
+    /// JSON schema of the parameter value, generated from a [`JsonSchema`] type
+    pub json_schema: Option<SchemaObject>,
+    /// Value used when the parameter is omitted
+    pub default: Option<serde_json::Value>,
+    /// Example value shown in the documentation
+    pub example: Option<serde_json::Value>,
+    /// Exhaustive list of accepted values
+    pub enum_values: Option<Vec<serde_json::Value>>,
+    /// Smallest accepted numeric value
+    pub minimum: Option<f64>,
+    /// Largest accepted numeric value
+    pub maximum: Option<f64>,
+    /// Minimum length in characters
+    pub min_length: Option<u32>,
+    /// Maximum length in characters
+    pub max_length: Option<u32>,
+    /// Regular expression the value must match
+    pub pattern: Option<String>,
+}
+
+impl ParamSchema {
+    /// Returns the JSON schema for the parameter, including default and enum values.
+    pub fn to_schema_object(&self) -> SchemaObject {
+        let mut schema = self.json_schema.clone().unwrap_or_else(|| SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        });
+
+        if let Some(default) = &self.default {
+            schema.metadata().default = Some(default.clone());
+        }
+
+        if let Some(values) = &self.enum_values {
+            schema.enum_values = Some(values.clone());
+        }
+
+        // Constraints of array parameters apply to each item
+        let target = match item_schema(&mut schema) {
+            Some(items) => items,
+            None => &mut schema,
+        };
+
+        if self.minimum.is_some() || self.maximum.is_some() {
+            if self.json_schema.is_none() {
+                target.instance_type = Some(InstanceType::Number.into());
+            }
+            let number = target.number();
+            number.minimum = self.minimum.or(number.minimum);
+            number.maximum = self.maximum.or(number.maximum);
+        }
+
+        if self.min_length.is_some() || self.max_length.is_some() || self.pattern.is_some() {
+            let string = target.string();
+            string.min_length = self.min_length.or(string.min_length);
+            string.max_length = self.max_length.or(string.max_length);
+            string.pattern = self.pattern.clone().or(string.pattern.take());
+        }
+
+        schema
+    }
+
+    /// Returns whether any constraint is enforced on the parameter value.
+    pub(crate) fn has_constraints(&self) -> bool {
+        self.enum_values.is_some()
+            || self.minimum.is_some()
+            || self.maximum.is_some()
+            || self.min_length.is_some()
+            || self.max_length.is_some()
+            || self.pattern.is_some()
+            || self.value_type().is_some()
+    }
+
+    /// Returns the documented numeric bounds of the value.
+    ///
+    /// Bounds set with [`ParamBuilder::minimum()`] and [`ParamBuilder::maximum()`] take
+    /// precedence over those of the schema, which are narrowed to the range of its
+    /// integer `format` (such as `uint32`). For array schemas these are the bounds of
+    /// each item.
+    pub(crate) fn bounds(&self) -> (Option<f64>, Option<f64>) {
+        let mut schema = self.to_schema_object();
+        let schema = match item_schema(&mut schema) {
+            Some(items) => items,
+            None => &mut schema,
+        };
+        let (mut minimum, mut maximum) = schema
+            .number
+            .as_ref()
+            .map_or((None, None), |number| (number.minimum, number.maximum));
+
+        if let Some((min, max)) = schema.format.as_deref().and_then(format_range) {
+            minimum = Some(minimum.map_or(min, |minimum| minimum.max(min)));
+            maximum = Some(maximum.map_or(max, |maximum| maximum.min(max)));
+        }
+        (minimum, maximum)
+    }
+
+    /// Returns the scalar type the value must parse as, from the documented schema.
+    ///
+    /// For array schemas this is the type of each item.
+    pub(crate) fn value_type(&self) -> Option<InstanceType> {
+        let mut schema = self.json_schema.clone()?;
+        let schema = match item_schema(&mut schema) {
+            Some(items) => items,
+            None => &mut schema,
+        };
+        let Some(SingleOrVec::Single(instance_type)) = &schema.instance_type else {
+            return None;
+        };
+        matches!(
+            **instance_type,
+            InstanceType::Integer | InstanceType::Number | InstanceType::Boolean
+        )
+        .then_some(**instance_type)
+    }
+}
+
+/// Returns the range of an integer `format` narrower than `i64` or `u64`.
+fn format_range(format: &str) -> Option<(f64, f64)> {
+    let range = match format {
+        "int8" => (i8::MIN as f64, i8::MAX as f64),
+        "int16" => (i16::MIN as f64, i16::MAX as f64),
+        "int32" => (i32::MIN as f64, i32::MAX as f64),
+        "uint8" => (0.0, u8::MAX as f64),
+        "uint16" => (0.0, u16::MAX as f64),
+        "uint32" => (0.0, u32::MAX as f64),
+        _ => return None,
+    };
+    Some(range)
+}
+
+/// Returns the item schema of an array schema with a single item type.
+fn item_schema(schema: &mut SchemaObject) -> Option<&mut SchemaObject> {
+    let is_array = matches!(
+        &schema.instance_type,
+        Some(SingleOrVec::Single(instance_type)) if **instance_type == InstanceType::Array
+    );
+    if !is_array {
+        return None;
+    }
+    match schema.array.as_mut()?.items.as_mut()? {
+        SingleOrVec::Single(items) => match items.as_mut() {
+            Schema::Object(items) => Some(items),
+            Schema::Bool(_) => None,
+        },
+        SingleOrVec::Vec(_) => None,
+    }
+}
+
+/// Typed parameter struct declared with [`Route::query_as()`] or [`Route::path_as()`].
+///
+/// Holds the functions used to deserialize the struct before the handler runs and to
+/// document its fields as OpenAPI parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct TypedParams {
+    pub(crate) decode: fn(&str) -> Result<TypedValue, String>,
+    pub(crate) document: fn(&mut Operation),
+}
+
+impl TypedParams {
+    fn query<T>() -> Self
+    where
+        T: DeserializeOwned + JsonSchema + Send + Sync + 'static,
+    {
+        Self {
+            decode: TypedValue::decode::<T>,
+            document: |operation| {
+                aide::r#gen::in_context(|ctx| {
+                    <axum::extract::Query<T> as aide::OperationInput>::operation_input(
+                        ctx, operation,
+                    )
+                })
+            },
+        }
+    }
+
+    fn path<T>() -> Self
+    where
+        T: DeserializeOwned + JsonSchema + Send + Sync + 'static,
+    {
+        Self {
+            decode: TypedValue::decode::<T>,
+            document: |operation| {
+                aide::r#gen::in_context(|ctx| {
+                    <axum::extract::Path<T> as aide::OperationInput>::operation_input(
+                        ctx, operation,
+                    )
+                })
+            },
+        }
+    }
 }
 
 /// Builder for configuring route parameters with a fluent API.
 ///
-/// Created by [`Route::query()`] and [`Route::path_param()`] methods. Allows chaining
-/// `.required()` and `.desc()` calls to configure parameter metadata for OpenAPI documentation.
+/// Created by [`Route::query()`], [`Route::path_param()`], [`Route::header()`] and
+/// [`Route::cookie()`] methods. Allows chaining `.required()`, `.desc()`,
+/// `.schema::<T>()`, `.default()`, `.example()`, `.enum_values()` and `.style()` calls
+/// to configure parameter metadata for OpenAPI documentation.
+///
+/// Constraints set with `.required()`, `.schema::<T>()` (for integer, number and
+/// boolean types, including the bounds of types such as `u32`), `.enum_values()`,
+/// `.min()`, `.max()`, `.min_length()`, `.max_length()` and `.pattern()` are also
+/// checked before the handler runs.
+/// Violations are rejected with a 422 `validation_failed` error whose details map
+/// each parameter name to its messages, like body validation. A missing required
+/// parameter is reported as `Parameter is required`, whether it is a query, path,
+/// header or cookie parameter.
+///
+/// # Example
+///
+/// ```rust
+/// use uncovr::server::endpoint::Route;
+///
+/// let mut route = Route::get("/users");
+/// route.query("page").schema::<u32>().default(1).min(1).desc("Page number");
+/// route.query("status").enum_values(["active", "disabled"]);
+/// route.query("q").min_length(2).max_length(50);
+/// ```
 #[derive(Debug)]
 pub struct ParamBuilder<'a> {
     route: &'a mut Route,
@@ -60,11 +526,16 @@ pub struct ParamBuilder<'a> {
 enum ParamType {
     Query(usize),
     Path(usize),
+    Header(usize),
+    Cookie(usize),
 }
 
 impl<'a> ParamBuilder<'a> {
-    /// Marks the parameter as required in the OpenAPI specification.
-    pub fn required(self) -> &'a mut Route {
+    /// Marks the parameter as required.
+    ///
+    /// Requests without the parameter are rejected with a 422 `validation_failed` error,
+    /// in every location.
+    pub fn required(self) -> Self {
         match self.param_type {
             ParamType::Query(idx) => {
                 if let Some(param) = self.route.query_params.get_mut(idx) {
@@ -76,12 +547,22 @@ impl<'a> ParamBuilder<'a> {
                     param.required = true;
                 }
             }
+            ParamType::Header(idx) => {
+                if let Some(param) = self.route.header_params.get_mut(idx) {
+                    param.required = true;
+                }
+            }
+            ParamType::Cookie(idx) => {
+                if let Some(param) = self.route.cookie_params.get_mut(idx) {
+                    param.required = true;
+                }
+            }
         }
-        self.route
+        self
     }
 
     /// Adds a description to the parameter for OpenAPI documentation.
-    pub fn desc(self, description: &'static str) -> &'a mut Route {
+    pub fn desc(self, description: &'static str) -> Self {
         match self.param_type {
             ParamType::Query(idx) => {
                 if let Some(param) = self.route.query_params.get_mut(idx) {
@@ -93,8 +574,159 @@ impl<'a> ParamBuilder<'a> {
                     param.description = Some(description);
                 }
             }
+            ParamType::Header(idx) => {
+                if let Some(param) = self.route.header_params.get_mut(idx) {
+                    param.description = Some(description);
+                }
+            }
+            ParamType::Cookie(idx) => {
+                if let Some(param) = self.route.cookie_params.get_mut(idx) {
+                    param.description = Some(description);
+                }
+            }
+        }
+        self
+    }
+
+    /// Documents the parameter with the JSON schema of `T`.
+    ///
+    /// Values of integer, number and boolean schemas are checked to parse as such;
+    /// handlers still read them with `Query::parse` and `Path::parse`.
+    pub fn schema<T: JsonSchema>(mut self) -> Self {
+        let json_schema = SchemaSettings::draft07()
+            .with(|settings| settings.inline_subschemas = true)
+            .into_generator()
+            .into_root_schema_for::<T>()
+            .schema;
+
+        if let Some(schema) = self.param_schema() {
+            schema.json_schema = Some(json_schema);
+        }
+        self
+    }
+
+    /// Sets the value documented as the parameter default.
+    pub fn default(mut self, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).ok();
+        if let Some(schema) = self.param_schema() {
+            schema.default = value;
+        }
+        self
+    }
+
+    /// Sets an example value for the parameter.
+    pub fn example(mut self, value: impl Serialize) -> Self {
+        let value = serde_json::to_value(value).ok();
+        if let Some(schema) = self.param_schema() {
+            schema.example = value;
+        }
+        self
+    }
+
+    /// Restricts the values of the parameter to the given set.
+    pub fn enum_values<V: Serialize>(mut self, values: impl IntoIterator<Item = V>) -> Self {
+        let values = values
+            .into_iter()
+            .filter_map(|value| serde_json::to_value(value).ok())
+            .collect();
+        if let Some(schema) = self.param_schema() {
+            schema.enum_values = Some(values);
+        }
+        self
+    }
+
+    /// Sets the smallest accepted numeric value.
+    pub fn min(mut self, value: impl Into<f64>) -> Self {
+        let value = value.into();
+        if let Some(schema) = self.param_schema() {
+            schema.minimum = Some(value);
+        }
+        self
+    }
+
+    /// Sets the largest accepted numeric value.
+    pub fn max(mut self, value: impl Into<f64>) -> Self {
+        let value = value.into();
+        if let Some(schema) = self.param_schema() {
+            schema.maximum = Some(value);
+        }
+        self
+    }
+
+    /// Sets the minimum length of the value in characters.
+    pub fn min_length(mut self, length: u32) -> Self {
+        if let Some(schema) = self.param_schema() {
+            schema.min_length = Some(length);
+        }
+        self
+    }
+
+    /// Sets the maximum length of the value in characters.
+    pub fn max_length(mut self, length: u32) -> Self {
+        if let Some(schema) = self.param_schema() {
+            schema.max_length = Some(length);
+        }
+        self
+    }
+
+    /// Requires the value to match a regular expression.
+    ///
+    /// The pattern is not anchored; use `^` and `$` to match the whole value.
+    /// Registering the route panics if the pattern is invalid.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        if let Some(schema) = self.param_schema() {
+            schema.pattern = Some(pattern.to_string());
+        }
+        self
+    }
+
+    /// Sets how the query parameter is written in the query string.
+    ///
+    /// Read the values with `Query::get_all()` for [`ParamStyle::Repeated`],
+    /// `Query::get_list()` for [`ParamStyle::CommaSeparated`] and `Query::get_object()`
+    /// for [`ParamStyle::DeepObject`]. Constraints are checked on each value of
+    /// repeated and comma-separated parameters. Has no effect on path parameters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::server::endpoint::{ParamStyle, Route};
+    ///
+    /// let mut route = Route::get("/posts");
+    /// route.query("tag").schema::<Vec<String>>().style(ParamStyle::CommaSeparated);
+    /// ```
+    pub fn style(self, style: ParamStyle) -> Self {
+        if let ParamType::Query(idx) = self.param_type {
+            if let Some(param) = self.route.query_params.get_mut(idx) {
+                param.style = Some(style);
+            }
+        }
+        self
+    }
+
+    fn param_schema(&mut self) -> Option<&mut ParamSchema> {
+        match self.param_type {
+            ParamType::Query(idx) => self
+                .route
+                .query_params
+                .get_mut(idx)
+                .map(|param| &mut param.schema),
+            ParamType::Path(idx) => self
+                .route
+                .path_params
+                .get_mut(idx)
+                .map(|param| &mut param.schema),
+            ParamType::Header(idx) => self
+                .route
+                .header_params
+                .get_mut(idx)
+                .map(|param| &mut param.schema),
+            ParamType::Cookie(idx) => self
+                .route
+                .cookie_params
+                .get_mut(idx)
+                .map(|param| &mut param.schema),
         }
-        self.route
     }
 }
 
@@ -102,22 +734,69 @@ impl<'a> ParamBuilder<'a> {
 ///
 /// Specifies the HTTP method, path, and parameter metadata for an endpoint.
 /// Used with [`Endpoint::route()`] to configure routing behavior.
+///
+/// Path parameters declared with [`Route::path_param()`] or [`Route::param()`] must
+/// match the `:name` and `*wildcard` segments of the path exactly; registration fails
+/// otherwise. When none are declared, they are documented from the path itself.
 #[derive(Debug, Clone)]
 pub struct Route {
     pub path: &'static str,
     pub method: HttpMethod,
     pub query_params: Vec<QueryParam>,
     pub path_params: Vec<PathParam>,
+    /// Request headers declared with [`Route::header()`]
+    pub header_params: Vec<HeaderParam>,
+    /// Request cookies declared with [`Route::cookie()`]
+    pub cookie_params: Vec<CookieParam>,
+    /// Typed query struct declared with [`Route::query_as()`]
+    pub typed_query: Option<TypedParams>,
+    /// Typed path struct declared with [`Route::path_as()`]
+    pub typed_path: Option<TypedParams>,
+    /// Request body policy, defaulting to [`BodyPolicy::for_method()`]
+    pub body: BodyPolicy,
+    /// Accepted request body encodings, set with [`Route::encoding()`] or [`Route::encodings()`]
+    pub encodings: Vec<BodyEncoding>,
+    /// Maximum request body size in bytes set with [`Route::max_body_size()`]
+    pub max_body_size: Option<usize>,
+    /// Maximum size in bytes of each uploaded file set with [`Route::max_file_size()`]
+    pub max_file_size: Option<usize>,
+    /// Pagination settings declared with [`Route::paginate()`]
+    pub pagination: Option<Paginate>,
+    /// Filterable and sortable fields declared with [`Route::filter()`]
+    pub filter: Option<FilterSpec>,
 }
 
 impl Route {
     /// Creates a route with the specified HTTP method and path.
-    pub fn new(method: HttpMethod, path: &'static str) -> Self {
+    ///
+    /// Accepts either an [`HttpMethod`] or any [`http::Method`], including extension methods.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::http::Method;
+    /// use uncovr::server::Route;
+    ///
+    /// let propfind = Method::from_bytes(b"PROPFIND").unwrap();
+    /// let route = Route::new(propfind, "/files/*path");
+    /// ```
+    pub fn new(method: impl Into<HttpMethod>, path: &'static str) -> Self {
+        let method = method.into();
         Self {
             path,
+            body: BodyPolicy::for_method(&method),
             method,
             query_params: Vec::new(),
             path_params: Vec::new(),
+            header_params: Vec::new(),
+            cookie_params: Vec::new(),
+            typed_query: None,
+            typed_path: None,
+            encodings: BodyEncoding::DEFAULT.to_vec(),
+            max_body_size: None,
+            max_file_size: None,
+            pagination: None,
+            filter: None,
         }
     }
 
@@ -147,21 +826,32 @@ impl Route {
     }
 
     /// Creates an OPTIONS route.
+    ///
+    /// Responses include an `Allow` header listing every method registered for the path.
     pub fn options(path: &'static str) -> Self {
         Self::new(HttpMethod::OPTIONS, path)
     }
 
     /// Creates a HEAD route.
+    ///
+    /// The handler runs as usual and the response body is discarded.
     pub fn head(path: &'static str) -> Self {
         Self::new(HttpMethod::HEAD, path)
     }
 
+    /// Creates a TRACE route.
+    pub fn trace(path: &'static str) -> Self {
+        Self::new(HttpMethod::TRACE, path)
+    }
+
     /// Adds a query parameter and returns a builder for configuration.
     pub fn query(&mut self, name: &'static str) -> ParamBuilder<'_> {
         self.query_params.push(QueryParam {
             name,
             description: None,
             required: false,
+            schema: ParamSchema::default(),
+            style: None,
         });
 
         let idx = self.query_params.len() - 1;
@@ -171,12 +861,189 @@ impl Route {
         }
     }
 
+    /// Declares a typed struct for the query string.
+    ///
+    /// The query string is deserialized into `T` before the handler runs and is available
+    /// through `ctx.query_typed::<T>()`. Requests that fail to deserialize are rejected with
+    /// a 400 `invalid_query` error. The fields of `T` are documented as query parameters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use schemars::JsonSchema;
+    /// use serde::Deserialize;
+    /// use uncovr::server::Route;
+    ///
+    /// #[derive(Deserialize, JsonSchema)]
+    /// struct Paging {
+    ///     page: Option<u32>,
+    ///     limit: Option<u32>,
+    /// }
+    ///
+    /// let route = Route::get("/users").query_as::<Paging>();
+    /// ```
+    pub fn query_as<T>(mut self) -> Self
+    where
+        T: DeserializeOwned + JsonSchema + Send + Sync + 'static,
+    {
+        self.typed_query = Some(TypedParams::query::<T>());
+        self
+    }
+
+    /// Declares a typed struct for the path parameters.
+    ///
+    /// The path parameters are deserialized into `T` before the handler runs and are available
+    /// through `ctx.path_typed::<T>()`. Requests that fail to deserialize are rejected with
+    /// a 400 `invalid_path` error. The fields of `T` are documented as path parameters.
+    pub fn path_as<T>(mut self) -> Self
+    where
+        T: DeserializeOwned + JsonSchema + Send + Sync + 'static,
+    {
+        self.typed_path = Some(TypedParams::path::<T>());
+        self
+    }
+
+    /// Sets the request body policy.
+    ///
+    /// Endpoints whose request type cannot be built from an empty body (see
+    /// [`Handler::Request`](crate::api::Handler::Request)) should use
+    /// [`BodyPolicy::Required`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::server::{BodyPolicy, Route};
+    ///
+    /// // Accept an optional JSON body on a search endpoint
+    /// let route = Route::get("/search").body(BodyPolicy::Optional);
+    ///
+    /// // DELETE without reading a body at all
+    /// let route = Route::delete("/users/:id").body(BodyPolicy::None);
+    /// ```
+    pub fn body(mut self, policy: BodyPolicy) -> Self {
+        self.body = policy;
+        self
+    }
+
+    /// Accepts a single request body encoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::server::{BodyEncoding, Route};
+    ///
+    /// // HTML form submission
+    /// let route = Route::post("/login").encoding(BodyEncoding::Form);
+    ///
+    /// // File upload with a 5 MiB limit per file
+    /// let route = Route::post("/avatars")
+    ///     .encoding(BodyEncoding::Multipart)
+    ///     .max_file_size(5 * 1024 * 1024);
+    /// ```
+    pub fn encoding(mut self, encoding: BodyEncoding) -> Self {
+        self.encodings = vec![encoding];
+        self
+    }
+
+    /// Accepts several request body encodings, dispatching on the `Content-Type` header.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::server::{BodyEncoding, Route};
+    ///
+    /// // Accept both API clients and HTML forms
+    /// let route = Route::post("/subscribe")
+    ///     .encodings(&[BodyEncoding::Json, BodyEncoding::Form]);
+    /// ```
+    pub fn encodings(mut self, encodings: &[BodyEncoding]) -> Self {
+        self.encodings = encodings.to_vec();
+        self
+    }
+
+    /// Sets the maximum request body size in bytes.
+    ///
+    /// Overrides axum's default limit of 2 MB for this route. Larger bodies are
+    /// rejected with 413 Payload Too Large.
+    pub fn max_body_size(mut self, bytes: usize) -> Self {
+        self.max_body_size = Some(bytes);
+        self
+    }
+
+    /// Sets the maximum size in bytes of each file in a multipart body.
+    ///
+    /// Files are streamed and rejected with a 413 `file_too_large` error as soon as
+    /// they exceed the limit. The whole body is still bounded by [`Route::max_body_size()`].
+    pub fn max_file_size(mut self, bytes: usize) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Paginates the route with offset/limit or cursor pagination.
+    ///
+    /// Documents the `offset` (or `cursor`) and `limit` query parameters and reads them
+    /// before the handler runs; the handler gets the requested page from
+    /// `ctx.pagination()` and returns a [`Page`](crate::api::Page). A `limit` above the
+    /// maximum page size is reduced to it; values that are not positive integers (or
+    /// non-negative for `offset`) are rejected with a 400 `invalid_query` error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::api::Paginate;
+    /// use uncovr::server::Route;
+    ///
+    /// let route = Route::get("/users").paginate(Paginate::offset().max_limit(50));
+    /// ```
+    pub fn paginate(mut self, paginate: Paginate) -> Self {
+        match paginate.mode {
+            PaginationMode::Offset => {
+                self.query("offset")
+                    .schema::<u64>()
+                    .default(0)
+                    .desc("Number of items to skip");
+            }
+            PaginationMode::Cursor => {
+                self.query("cursor")
+                    .desc("Cursor of the page to return, from the `next` or `prev` field of a previous page");
+            }
+        }
+        self.query("limit")
+            .schema::<u32>()
+            .min(1)
+            .max(paginate.max_limit)
+            .default(paginate.default_limit)
+            .desc("Maximum number of items to return; larger values are reduced to the maximum page size");
+
+        self.pagination = Some(paginate);
+        self
+    }
+
+    /// Declares the fields the route can be filtered and sorted on.
+    ///
+    /// The query string is parsed before the handler runs, which reads the result
+    /// with `ctx.list_query()`. Filters on undeclared fields or with disallowed
+    /// operators, and sorts on undeclared fields, are rejected with a 400 error.
+    /// Every allowed filter and the `sort` parameter are documented in OpenAPI.
+    ///
+    /// See [`FilterSpec`] for the query syntax.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the spec's default sort names a field that is not sortable.
+    pub fn filter(mut self, spec: FilterSpec) -> Self {
+        spec.check_default_sort();
+        self.filter = Some(spec);
+        self
+    }
+
     /// Adds a path parameter with description.
     pub fn param(mut self, name: &'static str, description: &'static str) -> Self {
         self.path_params.push(PathParam {
             name,
             description: Some(description),
             required: true,
+            schema: ParamSchema::default(),
         });
         self
     }
@@ -187,6 +1054,7 @@ impl Route {
             name,
             description: None,
             required: false,
+            schema: ParamSchema::default(),
         });
 
         let idx = self.path_params.len() - 1;
@@ -195,6 +1063,56 @@ impl Route {
             param_type: ParamType::Path(idx),
         }
     }
+
+    /// Adds a request header parameter and returns a builder for configuration.
+    ///
+    /// Documented as an `in: header` parameter. Requests without a required header and
+    /// constraint violations are rejected with a 422 `validation_failed` error. Handlers read the value with `ctx.header()` or
+    /// `ctx.parse_header::<T>()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use uncovr::server::Route;
+    ///
+    /// let mut route = Route::put("/documents/:id");
+    /// route.header("X-Tenant-Id").required().desc("Tenant owning the document");
+    /// route.header("If-Match").desc("ETag of the version being replaced");
+    /// ```
+    pub fn header(&mut self, name: &'static str) -> ParamBuilder<'_> {
+        self.header_params.push(HeaderParam {
+            name,
+            description: None,
+            required: false,
+            schema: ParamSchema::default(),
+        });
+
+        let idx = self.header_params.len() - 1;
+        ParamBuilder {
+            route: self,
+            param_type: ParamType::Header(idx),
+        }
+    }
+
+    /// Adds a cookie parameter and returns a builder for configuration.
+    ///
+    /// Documented as an `in: cookie` parameter. Requests without a required cookie and
+    /// constraint violations are rejected with a 422 `validation_failed` error. Handlers read the value with `ctx.cookie()` or
+    /// `ctx.parse_cookie::<T>()`.
+    pub fn cookie(&mut self, name: &'static str) -> ParamBuilder<'_> {
+        self.cookie_params.push(CookieParam {
+            name,
+            description: None,
+            required: false,
+            schema: ParamSchema::default(),
+        });
+
+        let idx = self.cookie_params.len() - 1;
+        ParamBuilder {
+            route: self,
+            param_type: ParamType::Cookie(idx),
+        }
+    }
 }
 
 /// Callback type for configuring OpenAPI response schemas.
@@ -228,6 +1146,12 @@ pub enum SecurityScheme {
     /// Expects: `Authorization: Basic <base64(username:password)>`
     Basic,
 
+    /// Session cookie set by the [`Sessions`](crate::middleware::Sessions) middleware
+    Session {
+        /// Cookie name (e.g., "session")
+        cookie: &'static str,
+    },
+
     /// OAuth2 authentication with specific flows
     OAuth2 {
         /// OAuth2 flow types (e.g., "authorizationCode", "implicit", "clientCredentials")
@@ -246,6 +1170,24 @@ pub enum ApiKeyLocation {
     Cookie,
 }
 
+/// Error response declared on an endpoint with [`Meta::errors()`] or [`Meta::error()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeclaredError {
+    /// HTTP status code of the error
+    pub status: http::StatusCode,
+    /// Response description; defaults to the status code's reason phrase
+    pub description: Option<&'static str>,
+}
+
+/// Response header documented on an endpoint with [`Meta::response_header()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseHeader {
+    /// Header name, such as `"Location"`
+    pub name: &'static str,
+    /// Header description
+    pub description: Option<&'static str>,
+}
+
 /// Endpoint metadata for API documentation and OpenAPI specification generation.
 ///
 /// Defines human-readable information about endpoints including summaries, descriptions,
@@ -259,6 +1201,12 @@ pub struct Meta {
     pub response_config: Option<ResponseCallback>,
     /// Security schemes required for this endpoint
     pub security: Vec<SecurityScheme>,
+    /// Error responses the endpoint can return; `None` documents every inferred error
+    pub errors: Option<Vec<DeclaredError>>,
+    /// Success status code; `None` documents the statuses inferred from the response type
+    pub success_status: Option<http::StatusCode>,
+    /// Headers sent with success responses
+    pub response_headers: Vec<ResponseHeader>,
 }
 
 impl Meta {
@@ -307,6 +1255,83 @@ impl Meta {
         self
     }
 
+    /// Declares the error statuses this endpoint can return.
+    ///
+    /// Only the declared errors are documented, replacing the 4xx and 5xx responses
+    /// inferred from the handler's response type. Errors uncovr itself returns before
+    /// the handler runs (such as 415 for an unsupported body) are still documented.
+    ///
+    /// In debug builds, a warning is logged when the handler responds with an
+    /// error status that was not declared, including on routes for extension methods
+    /// created with [`Route::new()`], which are not documented.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use uncovr::server::endpoint::Meta;
+    /// use uncovr::prelude::StatusCode;
+    ///
+    /// Meta::new()
+    ///     .summary("Update a user")
+    ///     .errors(&[StatusCode::NOT_FOUND, StatusCode::CONFLICT])
+    /// # ;
+    /// ```
+    pub fn errors(mut self, statuses: &[http::StatusCode]) -> Self {
+        let errors = self.errors.get_or_insert_with(Vec::new);
+        errors.extend(statuses.iter().map(|&status| DeclaredError {
+            status,
+            description: None,
+        }));
+        self
+    }
+
+    /// Declares an error status this endpoint can return, with a description.
+    ///
+    /// See [`Meta::errors()`].
+    pub fn error(mut self, status: http::StatusCode, description: &'static str) -> Self {
+        self.errors
+            .get_or_insert_with(Vec::new)
+            .push(DeclaredError {
+                status,
+                description: Some(description),
+            });
+        self
+    }
+
+    /// Documents the status code of successful responses.
+    ///
+    /// Replaces the 2xx and 3xx responses inferred from the handler's response type
+    /// with a single response under `status`, keeping the inferred body schema.
+    /// Statuses without a body (204, 304 and redirects) are documented without one.
+    /// Useful with [`Reply`](crate::api::response::Reply), whose status is only
+    /// known at runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use uncovr::server::endpoint::Meta;
+    /// use uncovr::prelude::StatusCode;
+    ///
+    /// Meta::new()
+    ///     .summary("Queue an export")
+    ///     .success_status(StatusCode::ACCEPTED)
+    ///     .response_header("Location", "URL of the export job")
+    /// # ;
+    /// ```
+    pub fn success_status(mut self, status: http::StatusCode) -> Self {
+        self.success_status = Some(status);
+        self
+    }
+
+    /// Documents a header sent with successful responses.
+    pub fn response_header(mut self, name: &'static str, description: &'static str) -> Self {
+        self.response_headers.push(ResponseHeader {
+            name,
+            description: Some(description),
+        });
+        self
+    }
+
     /// Marks this endpoint as requiring authentication.
     ///
     /// This is a convenience method that adds Bearer token authentication.
@@ -408,6 +1433,129 @@ pub trait Endpoint {
     fn meta(&self) -> Meta {
         Meta::new()
     }
+
+    /// Returns the middleware applied to this endpoint only.
+    ///
+    /// See [`Layers`] for how they are ordered relative to the server's layers.
+    fn layers(&self) -> Layers {
+        Layers::new()
+    }
+}
+
+type ApiLayer = Box<dyn FnOnce(ApiMethodRouter) -> ApiMethodRouter + Send>;
+type PlainLayer = Box<dyn FnOnce(MethodRouter) -> MethodRouter + Send>;
+
+/// Middleware declared by an endpoint with [`Endpoint::layers()`].
+///
+/// The layers wrap only the endpoint's route, not the 405 response for other methods on
+/// its path. Like [`ServerBuilder::layer()`], each layer wraps the ones added before it,
+/// so the last one added runs first.
+///
+/// Server layers added with [`ServerBuilder::layer()`] or [`ServerBuilder::middleware()`]
+/// after the endpoint is registered wrap its route layers: a request passes through
+/// the server layers, then the endpoint's layers, then reaches the handler. Server layers
+/// added before `register()` do not apply to the endpoint.
+///
+/// [`ServerBuilder::layer()`]: crate::server::ServerBuilder::layer
+/// [`ServerBuilder::middleware()`]: crate::server::ServerBuilder::middleware
+///
+/// # Example
+///
+/// ```rust
+/// use uncovr::middleware::{BearerAuth, RateLimit};
+/// use uncovr::server::endpoint::{Endpoint, Layers, Route};
+/// use std::time::Duration;
+///
+/// async fn validate_token(token: String) -> Result<(), String> {
+///     if token == "secret" { Ok(()) } else { Err("Invalid token".to_string()) }
+/// }
+///
+/// struct DeleteUser;
+///
+/// impl Endpoint for DeleteUser {
+///     fn route(&self) -> Route {
+///         Route::delete("/users/:id")
+///     }
+///
+///     fn layers(&self) -> Layers {
+///         // Authentication runs first, then rate limiting
+///         Layers::new()
+///             .layer(RateLimit::new(10, Duration::from_secs(60)))
+///             .layer(BearerAuth::new(validate_token))
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Layers {
+    layers: Vec<(ApiLayer, PlainLayer)>,
+}
+
+impl Layers {
+    /// Creates an empty list of layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a Tower layer around the endpoint and the layers added before it.
+    ///
+    /// Accepts the same layers as [`ServerBuilder::layer()`](crate::server::ServerBuilder::layer)
+    /// whose services cannot fail.
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: tower::Layer<axum::routing::Route> + Clone + Send + Sync + 'static,
+        L::Service: Service<Request<Body>, Error = Infallible> + Clone + Send + 'static,
+        <L::Service as Service<Request<Body>>>::Response: axum::response::IntoResponse + 'static,
+        <L::Service as Service<Request<Body>>>::Future: Send + 'static,
+    {
+        let plain = layer.clone();
+        self.layers.push((
+            Box::new(move |route: ApiMethodRouter| route.route_layer(layer)),
+            Box::new(move |route: MethodRouter| route.route_layer(plain)),
+        ));
+        self
+    }
+
+    /// Adds a function middleware, like [`ServerBuilder::middleware()`](crate::server::ServerBuilder::middleware).
+    pub fn middleware<F>(self, middleware: F) -> Self
+    where
+        F: Fn(
+                Request<Body>,
+                axum::middleware::Next,
+            ) -> futures::future::BoxFuture<'static, axum::response::Response>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.layer(axum::middleware::from_fn(middleware))
+    }
+
+    /// Returns true if no layer was added.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Wraps a documented route in the layers.
+    pub(crate) fn apply(self, route: ApiMethodRouter) -> ApiMethodRouter {
+        self.layers
+            .into_iter()
+            .fold(route, |route, (layer, _)| layer(route))
+    }
+
+    /// Wraps an undocumented route, used for extension methods, in the layers.
+    pub(crate) fn apply_plain(self, route: MethodRouter) -> MethodRouter {
+        self.layers
+            .into_iter()
+            .fold(route, |route, (_, layer)| layer(route))
+    }
+}
+
+impl std::fmt::Debug for Layers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Layers")
+            .field("len", &self.layers.len())
+            .finish()
+    }
 }
 
 #[cfg(test)]
@@ -420,6 +1568,18 @@ mod tests {
         assert_eq!(HttpMethod::POST.as_str(), "post");
         assert_eq!(HttpMethod::PUT.as_str(), "put");
         assert_eq!(HttpMethod::DELETE.as_str(), "delete");
+        assert_eq!(HttpMethod::TRACE.as_str(), "trace");
+    }
+
+    #[test]
+    fn test_http_method_from_method() {
+        assert_eq!(HttpMethod::from(http::Method::HEAD), HttpMethod::HEAD);
+
+        let propfind = http::Method::from_bytes(b"PROPFIND").unwrap();
+        let route = Route::new(propfind.clone(), "/files");
+        assert_eq!(route.method, HttpMethod::Other(propfind.clone()));
+        assert_eq!(route.method.as_str(), "PROPFIND");
+        assert_eq!(route.method.to_method(), propfind);
     }
 
     #[test]
@@ -432,6 +1592,26 @@ mod tests {
         assert_eq!(route.method, HttpMethod::POST);
     }
 
+    #[test]
+    fn test_body_encoding_matches() {
+        assert!(BodyEncoding::Json.matches("application/json; charset=utf-8"));
+        assert!(BodyEncoding::Json.matches("application/merge-patch+json"));
+        assert!(!BodyEncoding::Json.matches("text/json"));
+        assert!(BodyEncoding::Form.matches("application/x-www-form-urlencoded"));
+        assert!(BodyEncoding::Multipart.matches("multipart/form-data; boundary=X"));
+        assert!(!BodyEncoding::Multipart.matches("application/json"));
+    }
+
+    #[test]
+    fn test_body_policy_defaults() {
+        assert_eq!(Route::get("/users").body, BodyPolicy::None);
+        assert_eq!(Route::post("/users").body, BodyPolicy::Required);
+        assert_eq!(Route::delete("/users/:id").body, BodyPolicy::Optional);
+
+        let route = Route::delete("/users/:id").body(BodyPolicy::None);
+        assert_eq!(route.body, BodyPolicy::None);
+    }
+
     #[test]
     fn test_route_with_params() {
         let mut route = Route::get("/users");
@@ -445,6 +1625,30 @@ mod tests {
         assert!(!route.query_params[1].required);
     }
 
+    #[test]
+    fn test_param_schema() {
+        let mut route = Route::get("/users");
+        route
+            .query("page")
+            .schema::<u32>()
+            .default(1)
+            .example(3)
+            .desc("Page number");
+        route.query("status").enum_values(["active", "disabled"]);
+
+        let page = &route.query_params[0];
+        assert_eq!(page.description, Some("Page number"));
+        assert_eq!(page.schema.example, Some(serde_json::json!(3)));
+
+        let schema = serde_json::to_value(page.schema.to_schema_object()).unwrap();
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["default"], 1);
+
+        let schema = serde_json::to_value(route.query_params[1].schema.to_schema_object()).unwrap();
+        assert_eq!(schema["type"], "string");
+        assert_eq!(schema["enum"], serde_json::json!(["active", "disabled"]));
+    }
+
     #[test]
     fn test_meta_builder() {
         let meta = Meta::new()
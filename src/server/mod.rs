@@ -45,14 +45,19 @@
 //! ```
 
 mod builder;
+pub mod cookies;
 pub mod endpoint;
 pub mod params;
 mod router;
+pub mod upload;
 
 pub use builder::{Server, ServerBuilder};
+pub use cookies::Cookies;
 pub use endpoint::{
-    ApiKeyLocation, Endpoint, HttpMethod, Meta, PathParam, QueryParam, ResponseCallback, Route,
-    SecurityScheme,
+    ApiKeyLocation, BodyEncoding, BodyPolicy, CookieParam, DeclaredError, Endpoint, HeaderParam,
+    HttpMethod, Layers, Meta, ParamSchema, ParamStyle, PathParam, QueryParam, ResponseCallback,
+    ResponseHeader, Route, SecurityScheme,
 };
 pub use params::{Path, Query};
 pub use router::RouterExt;
+pub use upload::{Upload, Uploads};
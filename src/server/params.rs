@@ -1,5 +1,38 @@
+use serde::de::DeserializeOwned;
+use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::Arc;
+
+/// Type-erased parameter struct deserialized before the handler runs.
+///
+/// Stored on [`Path`] and [`Query`] when the route declares a typed parameter
+/// struct with `Route::path_as()` or `Route::query_as()`.
+#[derive(Clone)]
+pub(crate) struct TypedValue(Arc<dyn Any + Send + Sync>);
+
+impl TypedValue {
+    /// Deserializes a typed parameter struct from a URL-encoded string.
+    pub(crate) fn decode<T>(encoded: &str) -> Result<Self, String>
+    where
+        T: DeserializeOwned + Send + Sync + 'static,
+    {
+        serde_urlencoded::from_str::<T>(encoded)
+            .map(|value| Self(Arc::new(value)))
+            .map_err(|err| err.to_string())
+    }
+
+    fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        self.0.downcast_ref::<T>()
+    }
+}
+
+impl fmt::Debug for TypedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TypedValue")
+    }
+}
 
 /// Path parameters extracted from the URL.
 ///
@@ -10,21 +43,44 @@ use std::str::FromStr;
 #[derive(Debug, Clone, Default)]
 pub struct Path {
     params: HashMap<String, String>,
+    typed: Option<TypedValue>,
 }
 
 impl Path {
     /// Create new Path from a HashMap
     pub fn new(params: HashMap<String, String>) -> Self {
-        Self { params }
+        Self {
+            params,
+            typed: None,
+        }
     }
 
     /// Create empty Path
     pub fn empty() -> Self {
         Self {
             params: HashMap::new(),
+            typed: None,
         }
     }
 
+    /// Attach the typed parameter struct declared on the route
+    pub(crate) fn with_typed(mut self, typed: TypedValue) -> Self {
+        self.typed = Some(typed);
+        self
+    }
+
+    /// Get the typed parameter struct declared with `Route::path_as::<T>()`
+    ///
+    /// Returns `None` if the route did not declare a struct of type `T`.
+    pub fn typed<T: 'static>(&self) -> Option<&T> {
+        self.typed.as_ref()?.downcast_ref::<T>()
+    }
+
+    /// Encode the raw parameters as a URL-encoded string
+    pub(crate) fn to_urlencoded(&self) -> String {
+        serde_urlencoded::to_string(&self.params).unwrap_or_default()
+    }
+
     /// Get a parameter as a string slice
     pub fn get(&self, key: &str) -> Option<&str> {
         self.params.get(key).map(|s| s.as_str())
@@ -103,24 +159,74 @@ impl Path {
 /// - `query.get("page")` returns `Some("2")`
 /// - `query.get::<u32>("page")` returns `Some(2)`
 /// - `query.get::<u32>("limit")` returns `Some(10)`
+///
+/// Every value of a repeated key is kept:
+/// - `?tag=a&tag=b` and `?tag[]=a&tag[]=b`: `query.get_all("tag")` returns `["a", "b"]`
+/// - `?tag=a,b&tag=c`: `query.get_list("tag")` returns `["a", "b", "c"]`
+/// - `?filter[status]=active&filter[owner]=me`: `query.get_object("filter")` returns
+///   `{"status": "active", "owner": "me"}`
+///
+/// The single-value accessors (`get`, `parse`, ...) return the last value of a repeated key.
 #[derive(Debug, Clone, Default)]
 pub struct Query {
     params: HashMap<String, String>,
+    pairs: Vec<(String, String)>,
+    typed: Option<TypedValue>,
 }
 
 impl Query {
     /// Create new Query from a HashMap
     pub fn new(params: HashMap<String, String>) -> Self {
-        Self { params }
+        Self {
+            pairs: params
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            params,
+            typed: None,
+        }
+    }
+
+    /// Create Query from key/value pairs, keeping every value of repeated keys
+    pub fn from_pairs(pairs: Vec<(String, String)>) -> Self {
+        Self {
+            params: pairs.iter().cloned().collect(),
+            pairs,
+            typed: None,
+        }
+    }
+
+    /// Parse a URL-encoded query string such as `page=2&tag=a&tag=b`
+    pub fn from_query_string(query: &str) -> Self {
+        Self::from_pairs(
+            form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect(),
+        )
     }
 
     /// Create empty Query
     pub fn empty() -> Self {
         Self {
             params: HashMap::new(),
+            pairs: Vec::new(),
+            typed: None,
         }
     }
 
+    /// Attach the typed parameter struct declared on the route
+    pub(crate) fn with_typed(mut self, typed: TypedValue) -> Self {
+        self.typed = Some(typed);
+        self
+    }
+
+    /// Get the typed parameter struct declared with `Route::query_as::<T>()`
+    ///
+    /// Returns `None` if the route did not declare a struct of type `T`.
+    pub fn typed<T: 'static>(&self) -> Option<&T> {
+        self.typed.as_ref()?.downcast_ref::<T>()
+    }
+
     /// Get a parameter as a string slice
     pub fn get(&self, key: &str) -> Option<&str> {
         self.params.get(key).map(|s| s.as_str())
@@ -191,6 +297,123 @@ impl Query {
     pub fn keys(&self) -> Vec<&str> {
         self.params.keys().map(|s| s.as_str()).collect()
     }
+
+    /// Get every value of a repeated parameter, from `key=...` and `key[]=...` pairs
+    pub fn get_all(&self, key: &str) -> Vec<&str> {
+        self.pairs
+            .iter()
+            .filter(|(name, _)| name == key || name.strip_suffix("[]") == Some(key))
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    /// Get every value of a list parameter, splitting comma-separated values
+    ///
+    /// Accepts `?tag=a,b`, `?tag=a&tag=b` and `?tag[]=a&tag[]=b`; empty items are skipped.
+    pub fn get_list(&self, key: &str) -> Vec<&str> {
+        self.get_all(key)
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// Parse every value of a list parameter, see [`Query::get_list()`]
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// // ?ids=1,2,3 or ?ids=1&ids=2&ids=3
+    /// let ids: Vec<u64> = ctx.query.parse_list("ids")?;
+    /// ```
+    pub fn parse_list<T: FromStr>(&self, key: &str) -> Result<Vec<T>, ParamError> {
+        self.get_list(key)
+            .into_iter()
+            .map(|value| {
+                value.parse().map_err(|_| ParamError::InvalidType {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                    expected: std::any::type_name::<T>(),
+                })
+            })
+            .collect()
+    }
+
+    /// Get a parameter written with bracket syntax as a JSON value
+    ///
+    /// `filter[status]=active&filter[range][min]=1&filter[tags][]=a` gives
+    /// `{"status": "active", "range": {"min": "1"}, "tags": ["a"]}`. Values are
+    /// strings and repeated keys become arrays. Returns `None` if no pair uses `key`
+    /// with brackets; plain `filter=1` pairs are ignored.
+    ///
+    /// When a key holds both a value and nested keys (`filter[a]=1&filter[a][b]=2`),
+    /// the pair that comes first wins and the other is ignored.
+    pub fn get_object(&self, key: &str) -> Option<serde_json::Value> {
+        let mut root = None;
+        for (name, value) in &self.pairs {
+            let Some(rest) = name.strip_prefix(key).filter(|rest| rest.starts_with('[')) else {
+                continue;
+            };
+            let Some(segments) = bracket_segments(rest) else {
+                continue;
+            };
+            insert_nested(&mut root, &segments, value);
+        }
+        root
+    }
+}
+
+/// Splits `[a][b][]` into `["a", "b", ""]`, or returns `None` if `rest` is not bracketed.
+fn bracket_segments(rest: &str) -> Option<Vec<&str>> {
+    let mut segments = Vec::new();
+    let mut rest = rest;
+    while !rest.is_empty() {
+        let (segment, tail) = rest.strip_prefix('[')?.split_once(']')?;
+        segments.push(segment);
+        rest = tail;
+    }
+    Some(segments)
+}
+
+/// Inserts a value at the path given by `segments`, turning repeated values into arrays.
+fn insert_nested(slot: &mut Option<serde_json::Value>, segments: &[&str], value: &str) {
+    use serde_json::Value;
+
+    let Some((segment, rest)) = segments.split_first() else {
+        let value = Value::String(value.to_string());
+        *slot = Some(match slot.take() {
+            None => value,
+            Some(Value::Array(mut values)) => {
+                values.push(value);
+                Value::Array(values)
+            }
+            Some(existing) => Value::Array(vec![existing, value]),
+        });
+        return;
+    };
+
+    if segment.is_empty() {
+        let mut element = None;
+        insert_nested(&mut element, rest, value);
+        let element = element.unwrap_or(Value::Null);
+        match slot {
+            Some(Value::Array(values)) => values.push(element),
+            Some(existing) => *existing = Value::Array(vec![existing.take(), element]),
+            None => *slot = Some(Value::Array(vec![element])),
+        }
+        return;
+    }
+
+    let object = slot.get_or_insert_with(|| Value::Object(Default::default()));
+    let Value::Object(map) = object else {
+        // A plain value and a nested one share the key; keep the plain value
+        return;
+    };
+    let mut child = map.remove(*segment);
+    insert_nested(&mut child, rest, value);
+    if let Some(child) = child {
+        map.insert(segment.to_string(), child);
+    }
 }
 
 /// Error type for parameter extraction
@@ -278,6 +501,58 @@ mod tests {
         assert_eq!(params.get("page"), Some("2"));
     }
 
+    #[test]
+    fn test_typed_params() {
+        #[derive(serde::Deserialize)]
+        struct Paging {
+            page: u32,
+            active: bool,
+        }
+
+        let typed = TypedValue::decode::<Paging>("page=3&active=true").unwrap();
+        let query = Query::empty().with_typed(typed);
+
+        let paging = query.typed::<Paging>().unwrap();
+        assert_eq!(paging.page, 3);
+        assert!(paging.active);
+        assert!(query.typed::<u32>().is_none());
+
+        assert!(TypedValue::decode::<Paging>("page=abc&active=true").is_err());
+    }
+
+    #[test]
+    fn test_query_multiple_values() {
+        let query = Query::from_query_string(
+            "tag=a&tag=b%2Cc&tag[]=d&ids=1,2,,3&page=1&page=2\
+             &filter[status]=active&filter[range][min]=1&filter[tags][]=x&filter[tags][]=y",
+        );
+
+        assert_eq!(query.get("page"), Some("2"));
+        assert_eq!(query.get_all("tag"), ["a", "b,c", "d"]);
+        assert_eq!(query.get_list("tag"), ["a", "b", "c", "d"]);
+        assert_eq!(query.parse_list::<u32>("ids").unwrap(), [1, 2, 3]);
+        assert!(query.parse_list::<u32>("tag").is_err());
+        assert!(query.get_all("missing").is_empty());
+
+        assert_eq!(
+            query.get_object("filter").unwrap(),
+            serde_json::json!({
+                "status": "active",
+                "range": { "min": "1" },
+                "tags": ["x", "y"],
+            })
+        );
+        assert!(query.get_object("page").is_none());
+        assert!(query.get_object("filters").is_none());
+
+        // Plain values do not mix with bracketed ones
+        let query = Query::from_query_string("filter=1&filter[a]=2&filter[a][b]=3");
+        assert_eq!(
+            query.get_object("filter").unwrap(),
+            serde_json::json!({ "a": "2" })
+        );
+    }
+
     #[test]
     fn test_query_parse() {
         let mut map = HashMap::new();
@@ -0,0 +1,140 @@
+use axum::body::Bytes;
+
+/// A file received in a `multipart/form-data` request body.
+///
+/// Routes opt into multipart bodies with
+/// `Route::encoding(BodyEncoding::Multipart)`; every part with a file name is
+/// collected as an `Upload` and read with `ctx.uploads()`.
+#[derive(Debug, Clone)]
+pub struct Upload {
+    name: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    data: Bytes,
+}
+
+impl Upload {
+    /// Create a new upload
+    pub fn new(
+        name: impl Into<String>,
+        file_name: Option<String>,
+        content_type: Option<String>,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            file_name,
+            content_type,
+            data: data.into(),
+        }
+    }
+
+    /// Form field name of the part
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// File name sent by the client, if any
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Content type sent by the client, if any
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// File contents
+    pub fn bytes(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// File size in bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Check if the file is empty
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Consume the upload and return its contents
+    pub fn into_bytes(self) -> Bytes {
+        self.data
+    }
+}
+
+/// Files received in a `multipart/form-data` request body, in request order.
+///
+/// Example: for a form with `<input type="file" name="avatar">`:
+/// - `uploads.get("avatar")` returns the first file sent as `avatar`
+/// - `uploads.get_all("avatar")` iterates over every file sent as `avatar`
+#[derive(Debug, Clone, Default)]
+pub struct Uploads {
+    files: Vec<Upload>,
+}
+
+impl Uploads {
+    /// Create new Uploads from a list of files
+    pub const fn new(files: Vec<Upload>) -> Self {
+        Self { files }
+    }
+
+    /// Get the first file uploaded under a field name
+    pub fn get(&self, name: &str) -> Option<&Upload> {
+        self.files.iter().find(|upload| upload.name == name)
+    }
+
+    /// Get all files uploaded under a field name
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Upload> + 'a {
+        self.files.iter().filter(move |upload| upload.name == name)
+    }
+
+    /// Iterate over all uploaded files
+    pub fn iter(&self) -> impl Iterator<Item = &Upload> {
+        self.files.iter()
+    }
+
+    /// Number of uploaded files
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Check if no files were uploaded
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl IntoIterator for Uploads {
+    type Item = Upload;
+    type IntoIter = std::vec::IntoIter<Upload>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.files.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uploads_lookup() {
+        let uploads = Uploads::new(vec![
+            Upload::new("photo", Some("a.png".into()), None, &b"a"[..]),
+            Upload::new("doc", None, Some("text/plain".into()), &b"doc"[..]),
+            Upload::new("photo", Some("b.png".into()), None, &b"bb"[..]),
+        ]);
+
+        assert_eq!(uploads.len(), 3);
+        assert_eq!(uploads.get("photo").unwrap().file_name(), Some("a.png"));
+        assert_eq!(uploads.get_all("photo").count(), 2);
+        assert_eq!(
+            uploads.get("doc").unwrap().content_type(),
+            Some("text/plain")
+        );
+        assert!(uploads.get("missing").is_none());
+    }
+}